@@ -0,0 +1,253 @@
+use std::cmp::{max, min};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    pub fn new(start: u64, end: u64) -> Self {
+        if start <= end {
+            Self { start, end }
+        } else {
+            Self {
+                start: end,
+                end: start,
+            }
+        }
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    // u128 since the full u64 range holds 2^64 sections
+    pub fn size(&self) -> u128 {
+        u128::from(self.end - self.start) + 1
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn is_adjacent(&self, other: &Interval) -> bool {
+        self.end.checked_add(1) == Some(other.start) || other.end.checked_add(1) == Some(self.start)
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if self.overlaps(other) {
+            Some(Interval {
+                start: max(self.start, other.start),
+                end: min(self.end, other.end),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        if self.overlaps(other) || self.is_adjacent(other) {
+            Some(Interval {
+                start: min(self.start, other.start),
+                end: max(self.end, other.end),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn difference(&self, other: &Interval) -> Vec<Interval> {
+        match self.intersection(other) {
+            None => vec![*self],
+            Some(common) => {
+                let mut result = vec![];
+                if self.start < common.start {
+                    result.push(Interval::new(self.start, common.start - 1));
+                }
+                if common.end < self.end {
+                    result.push(Interval::new(common.end + 1, self.end));
+                }
+                result
+            }
+        }
+    }
+
+    pub fn parse(interval_as_str: &str) -> Interval {
+        let mut parts = interval_as_str.trim().split('-');
+        let start = parts.next().unwrap().parse::<u64>().unwrap();
+        let end = parts.next().unwrap().parse::<u64>().unwrap();
+        Interval::new(start, end)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let first = self
+            .intervals
+            .partition_point(|elem| elem.end.saturating_add(1) < interval.start);
+        let mut merged = interval;
+        let mut last = first;
+        while let Some(elem) = self.intervals.get(last) {
+            match merged.union(elem) {
+                Some(union) => {
+                    merged = union;
+                    last += 1;
+                }
+                None => break,
+            }
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self.intervals.partition_point(|elem| elem.end < value);
+        match self.intervals.get(index) {
+            Some(elem) => elem.contains(value),
+            None => false,
+        }
+    }
+
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|elem| elem.end < interval.start);
+        match self.intervals.get(index) {
+            Some(elem) => elem.contains_interval(interval),
+            None => false,
+        }
+    }
+
+    pub fn covered_size(&self) -> u128 {
+        self.intervals
+            .iter()
+            .fold(0, |accu, interval| accu + interval.size())
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.intervals
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end + 1, pair[1].start - 1))
+            .collect()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::parse("2-8");
+        assert_eq!(interval.size(), 7);
+        assert!(interval.contains(2));
+        assert!(interval.contains(8));
+        assert!(!interval.contains(9));
+        assert!(interval.contains_interval(&Interval::new(3, 7)));
+        assert!(!interval.contains_interval(&Interval::new(3, 9)));
+        assert!(interval.overlaps(&Interval::new(8, 9)));
+        assert!(!interval.overlaps(&Interval::new(9, 10)));
+        assert_eq!(
+            interval.intersection(&Interval::new(5, 12)),
+            Some(Interval::new(5, 8))
+        );
+        assert_eq!(interval.intersection(&Interval::new(9, 12)), None);
+        assert_eq!(
+            interval.union(&Interval::new(9, 12)),
+            Some(Interval::new(2, 12))
+        );
+        assert_eq!(interval.union(&Interval::new(10, 12)), None);
+        assert_eq!(
+            interval.difference(&Interval::new(4, 5)),
+            vec![Interval::new(2, 3), Interval::new(6, 8)]
+        );
+        assert_eq!(
+            interval.difference(&Interval::new(1, 5)),
+            vec![Interval::new(6, 8)]
+        );
+        assert_eq!(interval.difference(&Interval::new(1, 9)), vec![]);
+        assert_eq!(interval.to_string(), "2-8");
+    }
+
+    #[test]
+    fn test_large_interval() {
+        let interval = Interval::parse("1-4000000000");
+        assert_eq!(interval.size(), 4000000000);
+        assert!(interval.contains_interval(&Interval::parse("3999999999-4000000000")));
+        let interval = Interval::new(0, u64::MAX);
+        assert_eq!(interval.size(), 1 << 64);
+        assert!(interval.contains(u64::MAX));
+        assert_eq!(interval.union(&Interval::new(5, 6)), Some(interval));
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let set: IntervalSet = [
+            Interval::new(6, 8),
+            Interval::new(2, 4),
+            Interval::new(12, 14),
+            Interval::new(5, 5),
+            Interval::new(11, 11),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(2, 8), Interval::new(11, 14)]
+        );
+        assert_eq!(set.covered_size(), 11);
+        assert!(set.contains(5));
+        assert!(!set.contains(9));
+        assert!(set.contains_interval(&Interval::new(3, 7)));
+        assert!(!set.contains_interval(&Interval::new(7, 11)));
+        assert_eq!(set.gaps(), vec![Interval::new(9, 10)]);
+
+        let mut set = set;
+        set.insert(Interval::new(1, 20));
+        assert_eq!(set.intervals(), &[Interval::new(1, 20)]);
+        set.insert(Interval::new(0, u64::MAX));
+        assert_eq!(set.covered_size(), 1 << 64);
+    }
+}
@@ -1,48 +1,33 @@
+mod interval;
+mod overlap;
+mod reassign;
+mod render;
+
+pub use interval::{Interval, IntervalSet};
+pub use overlap::{ElfId, OverlapAnalysis};
+pub use reassign::{ElfReassignment, Reassignment, ReassignmentStrategy};
 use std::io;
-use std::ops::RangeInclusive;
 
-struct SectionAssignmentRange {
-    range: RangeInclusive<u32>,
-}
+pub struct AssignmentPair(pub Interval, pub Interval);
 
-impl SectionAssignmentRange {
-    fn is_in_range(&self, range: &SectionAssignmentRange) -> bool {
-        let mut in_range = true;
-        for value in self.range.clone() {
-            in_range = in_range && range.range.contains(&value);
-        }
-        in_range
-    }
-
-    fn has_number_overlap(&self, range: &SectionAssignmentRange) -> bool {
-        let mut overlap = false;
-        for value in self.range.clone() {
-            overlap = overlap || range.range.contains(&value);
-        }
-        overlap
+impl AssignmentPair {
+    pub fn parse(line: &str) -> Self {
+        let mut parts = line.split(',');
+        let part1 = Interval::parse(parts.next().unwrap());
+        let part2 = Interval::parse(parts.next().unwrap());
+        AssignmentPair(part1, part2)
     }
 
-    fn parse(range_as_str: &str) -> SectionAssignmentRange {
-        let mut parts = range_as_str.split('-');
-        let range = parts.next().unwrap().parse::<u32>().unwrap()
-            ..=parts.next().unwrap().parse::<u32>().unwrap();
-        SectionAssignmentRange { range }
+    pub fn is_fully_contained(&self) -> bool {
+        self.0.contains_interval(&self.1) || self.1.contains_interval(&self.0)
     }
-}
-
-struct AssignmentPair(SectionAssignmentRange, SectionAssignmentRange);
 
-impl AssignmentPair {
-    fn is_fully_contained(&self) -> bool {
-        self.0.is_in_range(&self.1) || self.1.is_in_range(&self.0)
+    pub fn has_overlap(&self) -> bool {
+        self.0.overlaps(&self.1)
     }
 
-    fn has_overlap(&self) -> bool {
-        if self.0.range.clone().count() > self.1.range.clone().count() {
-            self.0.has_number_overlap(&self.1)
-        } else {
-            self.1.has_number_overlap(&self.0)
-        }
+    pub fn overlap(&self) -> Option<Interval> {
+        self.0.intersection(&self.1)
     }
 }
 
@@ -54,15 +39,40 @@ impl SectionAssignments {
     pub fn parse(lines: &mut dyn Iterator<Item = Result<String, io::Error>>) -> Self {
         let mut assignments = vec![];
         for line in lines.flatten() {
-            let mut parts = line.split(',');
-            let part1 = SectionAssignmentRange::parse(parts.next().unwrap());
-            let part2 = SectionAssignmentRange::parse(parts.next().unwrap());
-            assignments.push(AssignmentPair(part1, part2));
+            assignments.push(AssignmentPair::parse(&line));
         }
 
         SectionAssignments { assignments }
     }
 
+    pub fn assignments(&self) -> &[AssignmentPair] {
+        &self.assignments
+    }
+
+    pub fn elves(&self) -> Vec<(ElfId, Interval)> {
+        self.assignments
+            .iter()
+            .enumerate()
+            .flat_map(|(pair, assignment_pair)| {
+                [
+                    (ElfId { pair, member: 0 }, assignment_pair.0),
+                    (ElfId { pair, member: 1 }, assignment_pair.1),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn overlap_analysis(&self) -> OverlapAnalysis {
+        OverlapAnalysis::from_assignments(self)
+    }
+
+    pub fn coverage(&self) -> IntervalSet {
+        self.assignments
+            .iter()
+            .flat_map(|assignment_pair| [assignment_pair.0, assignment_pair.1])
+            .collect()
+    }
+
     pub fn number_of_assignment_pairs_fully_contained(&self) -> u32 {
         self.assignments.iter().fold(0, |accu, assignment_pair| {
             accu + {
@@ -124,4 +134,23 @@ mod test {
             4
         );
     }
+
+    #[test]
+    fn test_large_assignments() {
+        let data = r#"1-4000000000,3999999999-5000000000
+1-4000000000,2-3999999999
+10-20,21-30"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let section_assignments = SectionAssignments::parse(&mut lines);
+        assert_eq!(
+            section_assignments.number_of_assignment_pairs_fully_contained(),
+            1
+        );
+        assert_eq!(section_assignments.number_of_assignment_pairs_overlaps(), 2);
+        assert_eq!(
+            section_assignments.coverage().intervals(),
+            &[Interval::new(1, 5000000000)]
+        );
+    }
 }
@@ -0,0 +1,114 @@
+use super::{Interval, SectionAssignments};
+use std::collections::BTreeSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElfId {
+    pub pair: usize,
+    pub member: usize,
+}
+
+impl fmt::Display for ElfId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.pair + 1, self.member + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum EventKind {
+    Start,
+    End,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OverlapAnalysis {
+    pub overlapping_elves: Vec<(ElfId, ElfId)>,
+    pub max_coverage: usize,
+    pub max_coverage_section: Option<u64>,
+    pub uncovered_sections: Vec<Interval>,
+}
+
+impl OverlapAnalysis {
+    pub fn from_assignments(section_assignments: &SectionAssignments) -> Self {
+        let elves = section_assignments.elves();
+        let mut events = Vec::with_capacity(elves.len() * 2);
+        for (index, (_, interval)) in elves.iter().enumerate() {
+            events.push((interval.start(), EventKind::Start, index));
+            events.push((interval.end(), EventKind::End, index));
+        }
+        // Starts are sorted before ends on the same section: bounds are inclusive
+        events.sort();
+
+        let mut overlapping_elves = vec![];
+        let mut max_coverage = 0;
+        let mut max_coverage_section = None;
+        let mut active = BTreeSet::<usize>::new();
+        for (section, kind, index) in events {
+            match kind {
+                EventKind::Start => {
+                    for other in active.iter() {
+                        let (elf, other_elf) = (elves[index].0, elves[*other].0);
+                        overlapping_elves.push((elf.min(other_elf), elf.max(other_elf)));
+                    }
+                    active.insert(index);
+                    if active.len() > max_coverage {
+                        max_coverage = active.len();
+                        max_coverage_section = Some(section);
+                    }
+                }
+                EventKind::End => {
+                    active.remove(&index);
+                }
+            }
+        }
+        overlapping_elves.sort();
+
+        OverlapAnalysis {
+            overlapping_elves,
+            max_coverage,
+            max_coverage_section,
+            uncovered_sections: section_assignments.coverage().gaps(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_overlap_analysis() {
+        let data = r#"2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+12-13,15-15"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let section_assignments = SectionAssignments::parse(&mut lines);
+        let analysis = section_assignments.overlap_analysis();
+
+        let elves = section_assignments.elves();
+        let mut expected = vec![];
+        for (index, (elf, interval)) in elves.iter().enumerate() {
+            for (other_elf, other_interval) in elves.iter().skip(index + 1) {
+                if interval.overlaps(other_interval) {
+                    expected.push((*elf, *other_elf));
+                }
+            }
+        }
+        assert_eq!(analysis.overlapping_elves, expected);
+        assert!(analysis
+            .overlapping_elves
+            .contains(&(ElfId { pair: 1, member: 0 }, ElfId { pair: 5, member: 0 })));
+        assert_eq!(analysis.max_coverage, 8);
+        assert_eq!(analysis.max_coverage_section, Some(6));
+        assert_eq!(
+            analysis.uncovered_sections,
+            vec![Interval::new(10, 11), Interval::new(14, 14)]
+        );
+    }
+}
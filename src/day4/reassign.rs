@@ -0,0 +1,307 @@
+use super::{ElfId, Interval, SectionAssignments};
+
+pub enum ReassignmentStrategy {
+    BalancedWorkload,
+    MinimumElves,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ElfReassignment {
+    pub elf: ElfId,
+    pub original: Interval,
+    pub assigned: Option<Interval>,
+}
+
+impl ElfReassignment {
+    pub fn released_sections(&self) -> Vec<Interval> {
+        match self.assigned {
+            Some(assigned) => self.original.difference(&assigned),
+            None => vec![self.original],
+        }
+    }
+
+    pub fn gained_sections(&self) -> Vec<Interval> {
+        match self.assigned {
+            Some(assigned) => assigned.difference(&self.original),
+            None => vec![],
+        }
+    }
+
+    pub fn workload_delta(&self) -> i128 {
+        let assigned = self.assigned.map_or(0, |assigned| assigned.size());
+        assigned as i128 - self.original.size() as i128
+    }
+}
+
+#[derive(Debug)]
+pub struct Reassignment {
+    pub elves: Vec<ElfReassignment>,
+}
+
+impl Reassignment {
+    pub fn has_overlap(&self) -> bool {
+        let mut assigned: Vec<Interval> = self
+            .elves
+            .iter()
+            .filter_map(|elf_reassignment| elf_reassignment.assigned)
+            .collect();
+        assigned.sort();
+        assigned.windows(2).any(|pair| pair[0].overlaps(&pair[1]))
+    }
+
+    pub fn number_of_elves_working(&self) -> usize {
+        self.elves
+            .iter()
+            .filter(|elf_reassignment| elf_reassignment.assigned.is_some())
+            .count()
+    }
+
+    pub fn moved_sections(&self) -> u128 {
+        self.elves.iter().fold(0, |accu, elf_reassignment| {
+            accu + elf_reassignment
+                .released_sections()
+                .iter()
+                .chain(elf_reassignment.gained_sections().iter())
+                .fold(0, |accu, interval| accu + interval.size())
+        })
+    }
+
+    fn balanced_workload(group: &[(ElfId, Interval)], component: Interval) -> Vec<ElfReassignment> {
+        // Every elf gives up the same amount of work, capped by its own workload
+        let excess = group
+            .iter()
+            .fold(0, |accu, (_, interval)| accu + interval.size())
+            - component.size();
+        let removed_below = |level: u128| {
+            group
+                .iter()
+                .fold(0, |accu, (_, interval)| accu + interval.size().min(level))
+        };
+        let (mut low, mut high) = (
+            0,
+            group
+                .iter()
+                .map(|(_, interval)| interval.size())
+                .max()
+                .unwrap(),
+        );
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if removed_below(middle) >= excess {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        let level = low;
+        let mut remainder = excess - removed_below(level.saturating_sub(1));
+
+        let mut result = vec![];
+        let mut cursor = component.start();
+        for (elf, original) in group {
+            let mut removed = original.size().min(level.saturating_sub(1));
+            if remainder > 0 && original.size() >= level && level > 0 {
+                removed += 1;
+                remainder -= 1;
+            }
+            let new_size = original.size() - removed;
+            let assigned = if new_size > 0 {
+                let end = cursor + u64::try_from(new_size - 1).unwrap();
+                let assigned = Interval::new(cursor, end);
+                cursor = end.saturating_add(1);
+                Some(assigned)
+            } else {
+                None
+            };
+            result.push(ElfReassignment {
+                elf: *elf,
+                original: *original,
+                assigned,
+            });
+        }
+        result
+    }
+
+    fn minimum_elves(group: &[(ElfId, Interval)], component: Interval) -> Vec<ElfReassignment> {
+        let mut assigned = vec![None; group.len()];
+        let mut cursor = component.start();
+        let mut index = 0;
+        loop {
+            let mut best: Option<usize> = None;
+            while index < group.len() && group[index].1.start() <= cursor {
+                if best.is_none_or(|best| group[index].1.end() > group[best].1.end()) {
+                    best = Some(index);
+                }
+                index += 1;
+            }
+            let best = best.unwrap();
+            assigned[best] = Some(Interval::new(cursor, group[best].1.end()));
+            if group[best].1.end() >= component.end() {
+                break;
+            }
+            cursor = group[best].1.end() + 1;
+        }
+
+        group
+            .iter()
+            .zip(assigned)
+            .map(|((elf, original), assigned)| ElfReassignment {
+                elf: *elf,
+                original: *original,
+                assigned,
+            })
+            .collect()
+    }
+}
+
+impl SectionAssignments {
+    pub fn reassign(&self, strategy: &ReassignmentStrategy) -> Reassignment {
+        let mut elves = self.elves();
+        elves.sort_by_key(|(elf, interval)| (*interval, *elf));
+
+        let mut result = vec![];
+        let mut group_start = 0;
+        while group_start < elves.len() {
+            let mut component = elves[group_start].1;
+            let mut group_end = group_start + 1;
+            while let Some(union) = elves
+                .get(group_end)
+                .and_then(|(_, interval)| component.union(interval))
+            {
+                component = union;
+                group_end += 1;
+            }
+            let group = &elves[group_start..group_end];
+            result.extend(match strategy {
+                ReassignmentStrategy::BalancedWorkload => {
+                    Reassignment::balanced_workload(group, component)
+                }
+                ReassignmentStrategy::MinimumElves => Reassignment::minimum_elves(group, component),
+            });
+            group_start = group_end;
+        }
+        result.sort_by_key(|elf_reassignment| elf_reassignment.elf);
+
+        Reassignment { elves: result }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day4::IntervalSet;
+    use crate::testtools::*;
+
+    fn sample() -> SectionAssignments {
+        let data = r#"2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+20-29,25-34"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        SectionAssignments::parse(&mut lines)
+    }
+
+    #[test]
+    fn test_balanced_workload() {
+        let section_assignments = sample();
+        let reassignment = section_assignments.reassign(&ReassignmentStrategy::BalancedWorkload);
+        assert!(!reassignment.has_overlap());
+        assert_eq!(
+            reassignment
+                .elves
+                .iter()
+                .filter_map(|elf_reassignment| elf_reassignment.assigned)
+                .collect::<IntervalSet>(),
+            section_assignments.coverage()
+        );
+
+        let last_pair: Vec<&ElfReassignment> = reassignment
+            .elves
+            .iter()
+            .filter(|elf_reassignment| elf_reassignment.elf.pair == 6)
+            .collect();
+        assert_eq!(last_pair[0].assigned, Some(Interval::new(20, 26)));
+        assert_eq!(
+            last_pair[0].released_sections(),
+            vec![Interval::new(27, 29)]
+        );
+        assert_eq!(last_pair[0].workload_delta(), -3);
+        assert_eq!(last_pair[1].assigned, Some(Interval::new(27, 34)));
+        assert_eq!(
+            last_pair[1].released_sections(),
+            vec![Interval::new(25, 26)]
+        );
+        assert_eq!(last_pair[1].gained_sections(), vec![]);
+        assert_eq!(last_pair[1].workload_delta(), -2);
+    }
+
+    #[test]
+    fn test_full_range() {
+        let data = r#"0-18446744073709551615,1-2
+0-18446744073709551615,0-18446744073709551615"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let section_assignments = SectionAssignments::parse(&mut lines);
+        for strategy in [
+            ReassignmentStrategy::BalancedWorkload,
+            ReassignmentStrategy::MinimumElves,
+        ] {
+            let reassignment = section_assignments.reassign(&strategy);
+            assert!(!reassignment.has_overlap());
+            let assigned: IntervalSet = reassignment
+                .elves
+                .iter()
+                .filter_map(|elf_reassignment| elf_reassignment.assigned)
+                .collect();
+            assert_eq!(assigned, section_assignments.coverage());
+            assert_eq!(assigned.covered_size(), 1 << 64);
+        }
+        let reassignment = section_assignments.reassign(&ReassignmentStrategy::BalancedWorkload);
+        assert_eq!(
+            reassignment
+                .elves
+                .iter()
+                .map(ElfReassignment::workload_delta)
+                .sum::<i128>(),
+            -(2 << 64) - 2
+        );
+    }
+
+    #[test]
+    fn test_minimum_elves() {
+        let section_assignments = sample();
+        let reassignment = section_assignments.reassign(&ReassignmentStrategy::MinimumElves);
+        assert!(!reassignment.has_overlap());
+        assert_eq!(
+            reassignment
+                .elves
+                .iter()
+                .filter_map(|elf_reassignment| elf_reassignment.assigned)
+                .collect::<IntervalSet>(),
+            section_assignments.coverage()
+        );
+        assert_eq!(reassignment.number_of_elves_working(), 4);
+        let working: Vec<(ElfId, Interval)> = reassignment
+            .elves
+            .iter()
+            .filter_map(|elf_reassignment| {
+                elf_reassignment
+                    .assigned
+                    .map(|assigned| (elf_reassignment.elf, assigned))
+            })
+            .collect();
+        assert_eq!(
+            working,
+            vec![
+                (ElfId { pair: 2, member: 1 }, Interval::new(9, 9)),
+                (ElfId { pair: 3, member: 0 }, Interval::new(2, 8)),
+                (ElfId { pair: 6, member: 0 }, Interval::new(20, 29)),
+                (ElfId { pair: 6, member: 1 }, Interval::new(30, 34)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use super::{AssignmentPair, Interval, SectionAssignments};
+use std::fmt::Write;
+
+const SVG_WIDTH: f64 = 800.0;
+const SVG_LABEL_WIDTH: f64 = 120.0;
+const SVG_ROW_HEIGHT: f64 = 16.0;
+const SVG_PAIR_GAP: f64 = 10.0;
+
+struct Scale {
+    origin: u64,
+    step: u64,
+    columns: u64,
+}
+
+impl Scale {
+    fn new(section_assignments: &SectionAssignments, max_width: usize) -> Option<Self> {
+        let coverage = section_assignments.coverage();
+        let first = coverage.intervals().first()?;
+        let last = coverage.intervals().last()?;
+        let max_width = u64::try_from(max_width.max(1)).unwrap();
+        // Like the puzzle, start at section 1 when everything fits on screen
+        let origin = if last.end() - first.start().min(1) < max_width {
+            first.start().min(1)
+        } else {
+            first.start()
+        };
+        let span = last.end() - origin;
+        let step = span / max_width + 1;
+        Some(Scale {
+            origin,
+            step,
+            columns: span / step + 1,
+        })
+    }
+
+    fn column(&self, index: u64) -> Interval {
+        let start = self.origin + index * self.step;
+        Interval::new(start, start.saturating_add(self.step - 1))
+    }
+
+    fn is_scaled(&self) -> bool {
+        self.step > 1
+    }
+}
+
+impl AssignmentPair {
+    fn render_row(interval: &Interval, scale: &Scale) -> String {
+        (0..scale.columns)
+            .map(|index| {
+                let column = scale.column(index);
+                if !scale.is_scaled() {
+                    if interval.contains(column.start()) {
+                        char::from_digit((column.start() % 10) as u32, 10).unwrap()
+                    } else {
+                        '.'
+                    }
+                } else if interval.contains_interval(&column) {
+                    '#'
+                } else if interval.overlaps(&column) {
+                    '+'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    fn render_ascii(&self, scale: &Scale) -> String {
+        let mut result = String::new();
+        for interval in [&self.0, &self.1] {
+            writeln!(
+                result,
+                "{}  {}",
+                Self::render_row(interval, scale),
+                interval
+            )
+            .unwrap();
+        }
+        if let Some(overlap) = self.overlap() {
+            let markers: String = (0..scale.columns)
+                .map(|index| {
+                    if overlap.overlaps(&scale.column(index)) {
+                        '^'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            writeln!(result, "{}  {}", markers, self.overlap_label().unwrap()).unwrap();
+        }
+        result
+    }
+
+    fn overlap_label(&self) -> Option<String> {
+        let overlap = self.overlap()?;
+        let kind = if self.is_fully_contained() {
+            "fully contained"
+        } else {
+            "overlap"
+        };
+        Some(format!("{} {}", kind, overlap))
+    }
+
+    // Widest text printed after the columns, separator included
+    fn label_width(&self) -> usize {
+        [self.0.to_string(), self.1.to_string()]
+            .into_iter()
+            .chain(self.overlap_label())
+            .map(|label| label.len() + 2)
+            .max()
+            .unwrap()
+    }
+}
+
+impl SectionAssignments {
+    pub fn render_ascii(&self, max_width: usize) -> String {
+        let label_width = self
+            .assignments
+            .iter()
+            .map(AssignmentPair::label_width)
+            .max()
+            .unwrap_or(0);
+        let scale = match Scale::new(self, max_width.saturating_sub(label_width)) {
+            Some(scale) => scale,
+            None => return String::new(),
+        };
+        let mut result = String::new();
+        if scale.is_scaled() {
+            writeln!(
+                result,
+                "1 column = {} sections from {}",
+                scale.step, scale.origin
+            )
+            .unwrap();
+            writeln!(result).unwrap();
+        }
+        let pairs: Vec<String> = self
+            .assignments
+            .iter()
+            .map(|assignment_pair| assignment_pair.render_ascii(&scale))
+            .collect();
+        result.push_str(&pairs.join("\n"));
+        // Labels wider than the screen are cut rather than pushing rows past it
+        result
+            .lines()
+            .map(|line| format!("{}\n", &line[..line.len().min(max_width)]))
+            .collect()
+    }
+
+    pub fn render_svg(&self) -> String {
+        let coverage = self.coverage();
+        let (origin, last) = match (coverage.intervals().first(), coverage.intervals().last()) {
+            (Some(first), Some(last)) => (first.start(), last.end()),
+            _ => (0, 0),
+        };
+        let unit = (SVG_WIDTH - SVG_LABEL_WIDTH) / ((last - origin) as f64 + 1.0);
+        let scale_x = |section: u64| -> f64 { SVG_LABEL_WIDTH + (section - origin) as f64 * unit };
+        let height = self.assignments.len() as f64 * (2.0 * SVG_ROW_HEIGHT + SVG_PAIR_GAP);
+
+        let mut result = String::new();
+        writeln!(
+            result,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="11">"#,
+            SVG_WIDTH, height
+        )
+        .unwrap();
+        for (index, assignment_pair) in self.assignments.iter().enumerate() {
+            let top = index as f64 * (2.0 * SVG_ROW_HEIGHT + SVG_PAIR_GAP);
+            if let Some(overlap) = assignment_pair.overlap() {
+                let color = if assignment_pair.is_fully_contained() {
+                    "#f5a623"
+                } else {
+                    "#d0021b"
+                };
+                writeln!(
+                    result,
+                    r#"  <rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="0.3"/>"#,
+                    scale_x(overlap.start()),
+                    top,
+                    overlap.size() as f64 * unit,
+                    2.0 * SVG_ROW_HEIGHT,
+                    color
+                )
+                .unwrap();
+            }
+            for (row, interval) in [&assignment_pair.0, &assignment_pair.1].iter().enumerate() {
+                let y = top + row as f64 * SVG_ROW_HEIGHT;
+                writeln!(
+                    result,
+                    r#"  <text x="4" y="{:.2}">{}</text>"#,
+                    y + SVG_ROW_HEIGHT - 4.0,
+                    interval
+                )
+                .unwrap();
+                writeln!(
+                    result,
+                    r##"  <rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#4a90e2"/>"##,
+                    scale_x(interval.start()),
+                    y + 2.0,
+                    interval.size() as f64 * unit,
+                    SVG_ROW_HEIGHT - 4.0
+                )
+                .unwrap();
+            }
+        }
+        result.push_str("</svg>\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_render_ascii() {
+        let data = r#"2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let section_assignments = SectionAssignments::parse(&mut lines);
+        assert_eq!(
+            section_assignments.render_ascii(80),
+            r#".234.....  2-4
+.....678.  6-8
+
+.23......  2-3
+...45....  4-5
+
+....567..  5-7
+......789  7-9
+      ^    overlap 7-7
+
+.2345678.  2-8
+..34567..  3-7
+  ^^^^^    fully contained 3-7
+
+.....6...  6-6
+...456...  4-6
+     ^     fully contained 6-6
+
+.23456...  2-6
+...45678.  4-8
+   ^^^     overlap 4-6
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_ascii_scaled() {
+        let data = r#"1-45,46-100
+1-100,26-75"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let section_assignments = SectionAssignments::parse(&mut lines);
+        let rendered = section_assignments.render_ascii(33);
+        assert!(rendered.lines().all(|line| line.len() <= 33));
+        assert_eq!(
+            rendered,
+            r#"1 column = 10 sections from 1
+
+####+.....  1-45
+....+#####  46-100
+
+##########  1-100
+..+####+..  26-75
+  ^^^^^^    fully contained 26-75
+"#
+        );
+        for max_width in [0, 1, 10, 20] {
+            let rendered = section_assignments.render_ascii(max_width);
+            assert!(rendered.lines().all(|line| line.len() <= max_width));
+        }
+        assert_eq!(
+            section_assignments.render_ascii(10).lines().last(),
+            Some("^  fully c")
+        );
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let data = r#"2-4,6-8
+2-8,3-7"#
+            .to_string();
+        let mut lines = read_from_string(&data);
+        let svg = SectionAssignments::parse(&mut lines).render_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("fill=\"#4a90e2\"").count(), 4);
+        assert_eq!(svg.matches("fill=\"#f5a623\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"#d0021b\"").count(), 0);
+        assert!(svg.contains(">3-7</text>"));
+    }
+}
@@ -0,0 +1,265 @@
+use super::{Move, MoveError, MoveMethod, SupplyStacks};
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    fn execute(
+        &self,
+        supply_stacks: &mut SupplyStacks,
+        crane_move: &Move,
+    ) -> Result<u64, MoveError>;
+}
+
+impl Crane for MoveMethod {
+    fn name(&self) -> String {
+        match self {
+            MoveMethod::Reorder => "CrateMover 9000".to_string(),
+            MoveMethod::KeepOrder => "CrateMover 9001".to_string(),
+        }
+    }
+
+    fn execute(
+        &self,
+        supply_stacks: &mut SupplyStacks,
+        crane_move: &Move,
+    ) -> Result<u64, MoveError> {
+        supply_stacks.apply_move(crane_move.count, crane_move.from, crane_move.to, self)?;
+        match self {
+            MoveMethod::Reorder => Ok(u64::from(crane_move.count)),
+            MoveMethod::KeepOrder => Ok(1),
+        }
+    }
+}
+
+pub struct CapacityCrane {
+    capacity: u32,
+}
+
+impl CapacityCrane {
+    pub fn new(capacity: u32) -> Self {
+        CapacityCrane {
+            capacity: capacity.max(1),
+        }
+    }
+}
+
+impl Crane for CapacityCrane {
+    fn name(&self) -> String {
+        format!("Capacity crane ({} crates)", self.capacity)
+    }
+
+    fn execute(
+        &self,
+        supply_stacks: &mut SupplyStacks,
+        crane_move: &Move,
+    ) -> Result<u64, MoveError> {
+        supply_stacks.check_move(crane_move)?;
+        let mut remaining = crane_move.count;
+        let mut lifts = 0;
+        while remaining > 0 {
+            let count = remaining.min(self.capacity);
+            supply_stacks.apply_move(
+                count,
+                crane_move.from,
+                crane_move.to,
+                &MoveMethod::KeepOrder,
+            )?;
+            remaining -= count;
+            lifts += 1;
+        }
+        Ok(lifts)
+    }
+}
+
+pub struct EnergyCrane {
+    move_method: MoveMethod,
+    energy_per_crate: u64,
+    energy_per_stack: u64,
+}
+
+impl EnergyCrane {
+    pub fn new(move_method: MoveMethod, energy_per_crate: u64, energy_per_stack: u64) -> Self {
+        EnergyCrane {
+            move_method,
+            energy_per_crate,
+            energy_per_stack,
+        }
+    }
+}
+
+impl Crane for EnergyCrane {
+    fn name(&self) -> String {
+        format!("Energy crane ({})", self.move_method.name())
+    }
+
+    fn execute(
+        &self,
+        supply_stacks: &mut SupplyStacks,
+        crane_move: &Move,
+    ) -> Result<u64, MoveError> {
+        self.move_method.execute(supply_stacks, crane_move)?;
+        let distance = u64::try_from(crane_move.from.abs_diff(crane_move.to)).unwrap();
+        Ok(
+            u64::from(crane_move.count)
+                * (self.energy_per_crate + self.energy_per_stack * distance),
+        )
+    }
+}
+
+pub struct AdjacentCrane {
+    move_method: MoveMethod,
+}
+
+impl AdjacentCrane {
+    pub fn new(move_method: MoveMethod) -> Self {
+        AdjacentCrane { move_method }
+    }
+}
+
+impl Crane for AdjacentCrane {
+    fn name(&self) -> String {
+        format!("Adjacent crane ({})", self.move_method.name())
+    }
+
+    fn execute(
+        &self,
+        supply_stacks: &mut SupplyStacks,
+        crane_move: &Move,
+    ) -> Result<u64, MoveError> {
+        supply_stacks.check_move(crane_move)?;
+        let mut cost = 0;
+        let mut position = crane_move.from;
+        while position != crane_move.to {
+            let next = if crane_move.to > position {
+                position + 1
+            } else {
+                position - 1
+            };
+            let hop = Move {
+                count: crane_move.count,
+                from: position,
+                to: next,
+            };
+            cost += self.move_method.execute(supply_stacks, &hop)?;
+            position = next;
+        }
+        Ok(cost)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CraneReport {
+    pub crane: String,
+    pub total_cost: u64,
+    pub top_of_each_stack: String,
+}
+
+impl SupplyStacks {
+    pub fn simulate<C: Crane + ?Sized>(
+        &self,
+        moves: &[Move],
+        crane: &C,
+    ) -> Result<CraneReport, MoveError> {
+        let mut supply_stacks = self.clone();
+        let mut total_cost = 0;
+        for crane_move in moves {
+            total_cost += crane.execute(&mut supply_stacks, crane_move)?;
+        }
+        Ok(CraneReport {
+            crane: crane.name(),
+            total_cost,
+            top_of_each_stack: supply_stacks.top_of_each_stack(),
+        })
+    }
+
+    pub fn compare_cranes(
+        &self,
+        moves: &[Move],
+        cranes: &[&dyn Crane],
+    ) -> Result<Vec<CraneReport>, MoveError> {
+        cranes
+            .iter()
+            .map(|crane| self.simulate(moves, *crane))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_compare_cranes() {
+        let complete = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        let moves = Move::parse_all(&mut lines);
+
+        let capacity_crane = CapacityCrane::new(2);
+        let energy_crane = EnergyCrane::new(MoveMethod::KeepOrder, 2, 1);
+        let adjacent_crane = AdjacentCrane::new(MoveMethod::KeepOrder);
+        let reports = supply_stacks
+            .compare_cranes(
+                &moves,
+                &[
+                    &MoveMethod::Reorder,
+                    &MoveMethod::KeepOrder,
+                    &capacity_crane,
+                    &energy_crane,
+                    &adjacent_crane,
+                ],
+            )
+            .unwrap();
+        let summary: Vec<(&str, u64, &str)> = reports
+            .iter()
+            .map(|report| {
+                (
+                    report.crane.as_str(),
+                    report.total_cost,
+                    report.top_of_each_stack.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("CrateMover 9000", 7, "CMZ"),
+                ("CrateMover 9001", 4, "MCD"),
+                ("Capacity crane (2 crates)", 5, "MCZ"),
+                ("Energy crane (CrateMover 9001)", 24, "MCD"),
+                ("Adjacent crane (CrateMover 9001)", 5, "MCD"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crane_errors() {
+        let header = r#"[A]
+[B] [C]
+ 1   2 "#
+            .to_string();
+        let mut lines = read_from_string(&header);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        let moves = vec![Move {
+            count: 3,
+            from: 0,
+            to: 1,
+        }];
+        assert!(supply_stacks
+            .simulate(&moves, &CapacityCrane::new(1))
+            .is_err());
+        assert!(supply_stacks
+            .simulate(&moves, &AdjacentCrane::new(MoveMethod::Reorder))
+            .is_err());
+    }
+}
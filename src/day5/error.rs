@@ -0,0 +1,74 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveErrorKind {
+    InvalidInstruction(String),
+    UnknownStack(usize),
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveError {
+    pub line: Option<usize>,
+    pub kind: MoveErrorKind,
+}
+
+impl MoveError {
+    pub fn new(kind: MoveErrorKind) -> Self {
+        MoveError { line: None, kind }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        match &self.kind {
+            MoveErrorKind::InvalidInstruction(instruction) => {
+                write!(f, "invalid instruction \"{}\"", instruction)
+            }
+            MoveErrorKind::UnknownStack(stack) => write!(f, "unknown stack {}", stack),
+            MoveErrorKind::NotEnoughCrates {
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot move {} crates from stack {} holding {}",
+                requested, stack, available
+            ),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanError {
+    // No move list leads to the target, for instance when the crates differ
+    Unreachable,
+    // The search gave up after visiting too many layouts
+    Exhausted,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "target layout cannot be reached"),
+            PlanError::Exhausted => write!(f, "search limit reached before finding the target"),
+        }
+    }
+}
+
+impl Error for PlanError {}
@@ -0,0 +1,222 @@
+use super::{Crane, Crate, Move, MoveError, SupplyStacks};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub crane_move: Move,
+    carried: Vec<Crate>,
+    placed: Vec<Crate>,
+}
+
+impl JournalEntry {
+    pub fn carried(&self) -> &[Crate] {
+        &self.carried
+    }
+
+    pub fn placed(&self) -> &[Crate] {
+        &self.placed
+    }
+}
+
+impl SupplyStacks {
+    pub(super) fn record(&mut self, crane_move: Move, carried: Vec<Crate>, placed: Vec<Crate>) {
+        self.journal.truncate(self.position);
+        self.journal.push(JournalEntry {
+            crane_move,
+            carried,
+            placed,
+        });
+        self.position += 1;
+    }
+
+    pub fn journal(&self) -> &[JournalEntry] {
+        &self.journal[..self.position]
+    }
+
+    pub fn step(&self) -> usize {
+        self.position
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        if self.position == 0 {
+            return None;
+        }
+        self.position -= 1;
+        let entry = &self.journal[self.position];
+        self.stacks[entry.crane_move.to].take(entry.placed.len());
+        self.stacks[entry.crane_move.from].put(&entry.carried);
+        Some(entry.crane_move)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let entry = self.journal.get(self.position)?;
+        self.stacks[entry.crane_move.from].take(entry.carried.len());
+        self.stacks[entry.crane_move.to].put(&entry.placed);
+        self.position += 1;
+        Some(entry.crane_move)
+    }
+
+    pub fn replay_to(&mut self, step: usize) -> bool {
+        if step > self.journal.len() {
+            return false;
+        }
+        while self.position > step {
+            self.undo();
+        }
+        while self.position < step {
+            self.redo();
+        }
+        true
+    }
+
+    pub(super) fn forget_journal(&mut self) {
+        self.journal.clear();
+        self.position = 0;
+    }
+
+    // Yielded states carry no journal, so walking a move list stays linear
+    pub fn steps<'a, C: Crane + ?Sized>(&self, moves: &'a [Move], crane: &'a C) -> Steps<'a, C> {
+        let mut supply_stacks = self.clone();
+        supply_stacks.forget_journal();
+        Steps {
+            supply_stacks,
+            moves: moves.iter(),
+            crane,
+        }
+    }
+}
+
+pub struct Steps<'a, C: Crane + ?Sized> {
+    supply_stacks: SupplyStacks,
+    moves: std::slice::Iter<'a, Move>,
+    crane: &'a C,
+}
+
+impl<'a, C: Crane + ?Sized> Iterator for Steps<'a, C> {
+    type Item = Result<SupplyStacks, MoveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let crane_move = self.moves.next()?;
+        let result = self.crane.execute(&mut self.supply_stacks, crane_move);
+        // No real run goes past a failed move
+        if result.is_err() {
+            self.moves = [].iter();
+        }
+        self.supply_stacks.forget_journal();
+        Some(result.map(|_| self.supply_stacks.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day5::MoveMethod;
+    use crate::testtools::*;
+
+    fn sample() -> (SupplyStacks, Vec<Move>) {
+        let complete = r#"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        (supply_stacks, Move::parse_all(&mut lines))
+    }
+
+    fn stack_ids(supply_stacks: &SupplyStacks) -> Vec<String> {
+        supply_stacks
+            .stacks
+            .iter()
+            .map(|stack| stack.crate_stack.iter().map(Crate::id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let (mut supply_stacks, moves) = sample();
+        for crane_move in moves.iter() {
+            supply_stacks
+                .apply_move(
+                    crane_move.count,
+                    crane_move.from,
+                    crane_move.to,
+                    &MoveMethod::Reorder,
+                )
+                .unwrap();
+        }
+        assert_eq!(supply_stacks.top_of_each_stack(), "CMZ");
+        assert_eq!(supply_stacks.journal().len(), 4);
+        let entry = &supply_stacks.journal()[1];
+        assert_eq!(entry.crane_move.to_string(), "move 3 from 1 to 3");
+        assert_eq!(
+            entry.carried().iter().map(Crate::id).collect::<String>(),
+            "ZND"
+        );
+        assert_eq!(
+            entry.placed().iter().map(Crate::id).collect::<String>(),
+            "DNZ"
+        );
+
+        assert_eq!(supply_stacks.undo(), Some(moves[3]));
+        assert_eq!(stack_ids(&supply_stacks), vec!["CM", "", "PDNZ"]);
+        assert!(supply_stacks.replay_to(0));
+        assert_eq!(stack_ids(&supply_stacks), vec!["ZN", "MCD", "P"]);
+        assert_eq!(supply_stacks.undo(), None);
+        assert_eq!(supply_stacks.redo(), Some(moves[0]));
+        assert_eq!(stack_ids(&supply_stacks), vec!["ZND", "MC", "P"]);
+        assert!(supply_stacks.replay_to(4));
+        assert_eq!(supply_stacks.top_of_each_stack(), "CMZ");
+        assert_eq!(supply_stacks.redo(), None);
+        assert!(!supply_stacks.replay_to(5));
+
+        supply_stacks.replay_to(2);
+        supply_stacks
+            .apply_move(1, 2, 0, &MoveMethod::Reorder)
+            .unwrap();
+        assert_eq!(supply_stacks.step(), 3);
+        assert_eq!(supply_stacks.redo(), None);
+        assert_eq!(stack_ids(&supply_stacks), vec!["Z", "MC", "PDN"]);
+    }
+
+    #[test]
+    fn test_steps() {
+        let (supply_stacks, moves) = sample();
+        let reorder: Vec<Vec<String>> = supply_stacks
+            .steps(&moves, &MoveMethod::Reorder)
+            .map(|state| stack_ids(&state.unwrap()))
+            .collect();
+        let keep_order: Vec<Vec<String>> = supply_stacks
+            .steps(&moves, &MoveMethod::KeepOrder)
+            .map(|state| stack_ids(&state.unwrap()))
+            .collect();
+        assert_eq!(reorder.len(), 4);
+        assert_eq!(reorder[0], keep_order[0]);
+        assert_eq!(reorder[1], vec!["", "MC", "PDNZ"]);
+        assert_eq!(keep_order[1], vec!["", "MC", "PZND"]);
+        assert_eq!(reorder[3], vec!["C", "M", "PDNZ"]);
+        assert!(supply_stacks
+            .steps(&moves, &MoveMethod::Reorder)
+            .all(|state| state.unwrap().journal().is_empty()));
+        assert_eq!(keep_order[3], vec!["M", "C", "PZND"]);
+        assert_eq!(supply_stacks.step(), 0);
+
+        let mut moves = moves;
+        moves.insert(
+            1,
+            Move {
+                count: 9,
+                from: 0,
+                to: 1,
+            },
+        );
+        let mut steps = supply_stacks.steps(&moves, &MoveMethod::Reorder);
+        assert!(steps.next().unwrap().is_ok());
+        assert!(steps.next().unwrap().is_err());
+        assert!(steps.next().is_none());
+    }
+}
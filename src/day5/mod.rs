@@ -1,65 +1,148 @@
-use std::collections::VecDeque;
+mod crane;
+mod error;
+mod journal;
+mod optimizer;
+mod planner;
+mod provenance;
+mod render;
+
+pub use crane::{AdjacentCrane, CapacityCrane, Crane, CraneReport, EnergyCrane};
+pub use error::{MoveError, MoveErrorKind, PlanError};
+pub use journal::{JournalEntry, Steps};
+pub use optimizer::OptimizedMoves;
+pub use provenance::CratePosition;
+use std::fmt;
 use std::io;
-use std::str::Chars;
 
-#[derive(Debug, PartialEq)]
-struct Crate {
-    id: char,
+const EMPTY_STACK_PLACEHOLDER: &str = "_";
+const MOVE_PATTERN: &str = r"move\s*(?P<move>\d+)\s*from\s*(?P<from>\d+)\s*to\s*(?P<to>\d+)";
+
+#[derive(Debug, Clone)]
+pub struct Crate {
+    id: String,
+    origin: CratePosition,
+    history: Vec<Move>,
 }
 
-#[derive(Debug, PartialEq)]
+impl Crate {
+    pub fn new(id: &str) -> Self {
+        Crate {
+            id: id.to_string(),
+            origin: CratePosition::default(),
+            history: vec![],
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn origin(&self) -> CratePosition {
+        self.origin
+    }
+
+    // Cranes splitting an instruction into lifts or hops record each of them
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+}
+
+// Crates with the same label are interchangeable, provenance is tracked by origin
+impl PartialEq for Crate {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 struct Stack {
     crate_stack: Vec<Crate>,
 }
 
 impl Stack {
-    fn pop(&mut self) -> Option<Crate> {
-        self.crate_stack.pop()
-    }
-
     fn push(&mut self, crate_element: &Crate) {
-        self.crate_stack.push(Crate {
-            id: crate_element.id,
-        });
+        self.crate_stack.push(crate_element.clone());
     }
 
     fn top(&self) -> Option<&Crate> {
         self.crate_stack.last()
     }
+
+    fn take(&mut self, count: usize) -> Vec<Crate> {
+        self.crate_stack.split_off(self.crate_stack.len() - count)
+    }
+
+    fn put(&mut self, crates: &[Crate]) {
+        for crate_element in crates {
+            self.push(crate_element);
+        }
+    }
 }
 
-pub struct SupplyStacks {
-    stacks: Vec<Stack>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub count: u32,
+    pub from: usize,
+    pub to: usize,
 }
 
-#[derive(Debug)]
-enum HeaderToken {
-    Crate(char),
-    Empty,
-    Index(usize),
-    EndOfLine,
-    Error,
+impl Move {
+    pub fn parse(line: &str) -> Option<Self> {
+        let regex_move = regex::Regex::new(MOVE_PATTERN).unwrap();
+        Self::parse_with(&regex_move, line)
+    }
+
+    fn parse_with(regex_move: &regex::Regex, line: &str) -> Option<Self> {
+        let names = regex_move.captures(line)?;
+        // Numbers too large to parse are invalid instructions rather than a panic
+        let count = names.name("move")?.as_str().parse::<u32>().ok()?;
+        let from = names.name("from")?.as_str().parse::<usize>().ok()?;
+        let to = names.name("to")?.as_str().parse::<usize>().ok()?;
+        Some(Move {
+            count,
+            from: from.checked_sub(1)?,
+            to: to.checked_sub(1)?,
+        })
+    }
+
+    pub fn parse_all(lines: &mut dyn Iterator<Item = Result<String, io::Error>>) -> Vec<Self> {
+        let regex_move = regex::Regex::new(MOVE_PATTERN).unwrap();
+        lines
+            .flatten()
+            .filter_map(|line| Self::parse_with(&regex_move, &line))
+            .collect()
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
 }
 
-#[derive(Debug)]
-enum ParseTokenState {
-    Start,
-    BeginCrate,
-    InCrate(char),
-    EndCrate(char),
-    BeginIndex(usize),
-    EndIndex(usize),
-    EndLine,
+#[derive(Debug, Clone)]
+pub struct SupplyStacks {
+    stacks: Vec<Stack>,
+    journal: Vec<JournalEntry>,
+    position: usize,
+    lines_read: usize,
 }
 
 #[derive(Debug, PartialEq)]
 enum LineType {
-    Crate(Vec<Option<char>>),
-    Index(usize),
+    Crate(Vec<(usize, String)>),
+    Index(Vec<(usize, usize)>),
     Empty,
     Other,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoveMethod {
     Reorder,
     KeepOrder,
@@ -67,33 +150,68 @@ pub enum MoveMethod {
 
 impl SupplyStacks {
     pub fn parse(lines: &mut dyn Iterator<Item = Result<String, io::Error>>) -> Self {
-        let stacks = Self::parse_header_lines(lines);
+        let (stacks, lines_read) = Self::parse_header_lines(lines);
+
+        let mut supply_stacks = SupplyStacks {
+            stacks,
+            journal: vec![],
+            position: 0,
+            lines_read,
+        };
+        supply_stacks.assign_origins();
+        supply_stacks
+    }
 
-        SupplyStacks { stacks }
+    pub fn parse_move<C: Crane + ?Sized>(
+        &mut self,
+        lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
+        crane: &C,
+    ) -> Result<(), MoveError> {
+        match self.run_moves(lines, crane, true).into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 
-    pub fn parse_move(
+    pub fn validate<C: Crane + ?Sized>(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
+        crane: &C,
+    ) -> Vec<MoveError> {
+        self.clone().run_moves(lines, crane, false)
+    }
+
+    fn run_moves<C: Crane + ?Sized>(
         &mut self,
         lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
-        move_method: &MoveMethod,
-    ) {
-        let regex_move =
-            regex::Regex::new(r"move\s*(?P<move>\d+)\s*from\s*(?P<from>\d+)\s*to\s*(?P<to>\d+)")
-                .unwrap();
-        for line in lines.flatten() {
-            let names = regex_move.captures(&line);
-            if let Some(names) = names {
-                let number_of_moves = names.name("move").unwrap().as_str().parse::<u32>().unwrap();
-                let from = names
-                    .name("from")
-                    .unwrap()
-                    .as_str()
-                    .parse::<usize>()
-                    .unwrap();
-                let to = names.name("to").unwrap().as_str().parse::<usize>().unwrap();
-                self.apply_move(number_of_moves, from - 1, to - 1, move_method);
+        crane: &C,
+        stop_on_error: bool,
+    ) -> Vec<MoveError> {
+        let regex_move = regex::Regex::new(MOVE_PATTERN).unwrap();
+        let mut errors = vec![];
+        for line in lines {
+            // Unreadable lines are skipped but still count for the line numbers
+            self.lines_read += 1;
+            let Ok(line) = line else {
+                continue;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let result = match Move::parse_with(&regex_move, &line) {
+                Some(crane_move) => crane.execute(self, &crane_move).map(|_| ()),
+                None => Err(MoveError::new(MoveErrorKind::InvalidInstruction(
+                    line.trim().to_string(),
+                ))),
+            };
+            if let Err(error) = result {
+                errors.push(error.at_line(self.lines_read));
+                if stop_on_error {
+                    break;
+                }
             }
         }
+        errors
     }
 
     pub fn apply_move(
@@ -102,43 +220,60 @@ impl SupplyStacks {
         from: usize,
         to: usize,
         move_method: &MoveMethod,
-    ) {
-        match move_method {
-            MoveMethod::Reorder => {
-                for _ in 1..=number_of_moves {
-                    let from_element = {
-                        let from = self.stacks.get_mut(from).unwrap();
-                        Some(from.pop().unwrap())
-                    };
+    ) -> Result<(), MoveError> {
+        self.check_move(&Move {
+            count: number_of_moves,
+            from,
+            to,
+        })?;
+
+        let crane_move = Move {
+            count: number_of_moves,
+            from,
+            to,
+        };
+        let count = usize::try_from(number_of_moves).unwrap();
+        let carried = self.stacks[from].take(count);
+        // Moving crates one at a time onto their own stack puts them back in place
+        let mut placed: Vec<Crate> = match move_method {
+            MoveMethod::Reorder if from != to => carried.iter().rev().cloned().collect(),
+            _ => carried.clone(),
+        };
+        // The journal keeps the crates as carried, so undo also restores their history
+        for crate_element in &mut placed {
+            crate_element.history.push(crane_move);
+        }
+        self.stacks[to].put(&placed);
 
-                    let to = self.stacks.get_mut(to).unwrap();
-                    to.push(&from_element.unwrap());
-                }
-            }
-            MoveMethod::KeepOrder => {
-                let mut tmp_vec = VecDeque::<Crate>::new();
-                for _ in 1..=number_of_moves {
-                    let from_element = {
-                        let from = self.stacks.get_mut(from).unwrap();
-                        Some(from.pop().unwrap())
-                    };
-                    tmp_vec.push_back(from_element.unwrap());
-                }
+        self.record(crane_move, carried, placed);
+        Ok(())
+    }
 
-                for _ in 1..=number_of_moves {
-                    let element = tmp_vec.pop_back().unwrap();
-                    let to = self.stacks.get_mut(to).unwrap();
-                    to.push(&element);
-                }
+    pub fn check_move(&self, crane_move: &Move) -> Result<(), MoveError> {
+        for stack in [crane_move.from, crane_move.to] {
+            if stack >= self.stacks.len() {
+                return Err(MoveError::new(MoveErrorKind::UnknownStack(stack + 1)));
             }
         }
+        let count = usize::try_from(crane_move.count).unwrap();
+        let available = self.stacks[crane_move.from].crate_stack.len();
+        if count > available {
+            return Err(MoveError::new(MoveErrorKind::NotEnoughCrates {
+                stack: crane_move.from + 1,
+                requested: count,
+                available,
+            }));
+        }
+        Ok(())
     }
 
     pub fn top_of_each_stack(&self) -> String {
         let mut result = String::new();
         for stack in self.stacks.iter() {
-            let top_element = stack.top().unwrap().id;
-            result.push(top_element);
+            match stack.top() {
+                Some(top_element) => result.push_str(&top_element.id),
+                None => result.push_str(EMPTY_STACK_PLACEHOLDER),
+            }
         }
 
         result
@@ -146,122 +281,97 @@ impl SupplyStacks {
 
     fn parse_header_lines(
         lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
-    ) -> Vec<Stack> {
-        let mut dimension: usize = 0;
+    ) -> (Vec<Stack>, usize) {
+        let mut columns = vec![];
         let mut temp_vec = vec![];
-        for line in lines.flatten() {
+        let mut lines_read = 0;
+        for line in lines {
+            lines_read += 1;
+            let Ok(line) = line else {
+                continue;
+            };
             let token = Self::parse_header_line(&line);
             match token {
                 LineType::Crate(crate_vec) => {
                     temp_vec.push(crate_vec);
                 }
-                LineType::Index(index) => dimension = index,
+                LineType::Index(indexes) => columns = indexes,
                 LineType::Empty => break,
-                _ => {}
+                LineType::Other => {}
             }
         }
 
-        let mut stacks = Vec::<Stack>::new();
-        let max_height = temp_vec.len();
-        for stack_id in 0..dimension {
-            let mut stack = Stack {
-                crate_stack: vec![],
+        // Stacks are numbered by the rank of their index, whatever the values drawn
+        columns.sort_by_key(|(_, index)| *index);
+        let mut stacks = vec![
+            Stack {
+                crate_stack: vec![]
             };
-            for height in 0..max_height {
-                if let Some(element) = temp_vec
-                    .get(max_height - height - 1)
-                    .unwrap()
-                    .get(stack_id)
-                    .unwrap()
-                {
-                    stack.crate_stack.push(Crate { id: *element });
+            columns.len()
+        ];
+        for crate_vec in temp_vec.iter().rev() {
+            for (position, id) in crate_vec {
+                // Crates belong to the stack whose index is drawn closest below them
+                let stack_id = columns
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, (column, _))| column.abs_diff(*position))
+                    .map(|(stack_id, _)| stack_id);
+                if let Some(stack_id) = stack_id {
+                    stacks[stack_id].crate_stack.push(Crate::new(id));
                 }
             }
-            stacks.push(stack);
         }
 
-        stacks
+        (stacks, lines_read)
     }
 
-    fn parse_header_token(chars: &mut Chars) -> HeaderToken {
-        let mut token_state = ParseTokenState::Start;
-        for _ in 0..=2 {
-            if let Some(char) = chars.next() {
-                match token_state {
-                    ParseTokenState::Start => match char {
-                        '[' => token_state = ParseTokenState::BeginCrate,
-                        '0'..='9' => {
-                            token_state = ParseTokenState::BeginIndex(
-                                usize::try_from(u32::from(char) - u32::from('0')).unwrap(),
-                            )
-                        }
-                        _ => {}
-                    },
-                    ParseTokenState::BeginCrate => {
-                        if let 'A'..='Z' = char {
-                            token_state = ParseTokenState::InCrate(char);
-                        }
-                    }
-                    ParseTokenState::InCrate(crate_id) => {
-                        if let ']' = char {
-                            token_state = ParseTokenState::EndCrate(crate_id);
-                        }
-                    }
-                    ParseTokenState::BeginIndex(number) => {
-                        if let ' ' = char {
-                            token_state = ParseTokenState::EndIndex(number);
-                        }
-                    }
-                    ParseTokenState::EndLine => {}
-                    ParseTokenState::EndIndex(_) => {}
-                    ParseTokenState::EndCrate(_) => {}
-                }
-            } else {
-                token_state = ParseTokenState::EndLine;
-            }
-        }
-        match token_state {
-            ParseTokenState::Start => HeaderToken::Empty,
-            ParseTokenState::EndCrate(crate_id) => HeaderToken::Crate(crate_id),
-            ParseTokenState::EndIndex(number) => HeaderToken::Index(number),
-            ParseTokenState::EndLine => HeaderToken::EndOfLine,
-            _ => HeaderToken::Error,
+    fn parse_header_line(line: &str) -> LineType {
+        if line.trim().is_empty() {
+            return LineType::Empty;
         }
-    }
 
-    fn parse_header_line(line: &str) -> LineType {
-        let mut result = LineType::Other;
-        let mut chars = line.chars();
-        while {
-            let token = Self::parse_header_token(&mut chars);
-            match token {
-                HeaderToken::EndOfLine => result = LineType::Empty,
-                HeaderToken::Crate(crate_id) => {
-                    if result == LineType::Other {
-                        result = LineType::Crate(vec![Some(crate_id)]);
-                    } else if let LineType::Crate(ref mut crates) = result {
-                        crates.push(Some(crate_id));
-                    }
-                }
-                HeaderToken::Empty => {
-                    if result == LineType::Other {
-                        result = LineType::Crate(vec![None]);
-                    } else if let LineType::Crate(ref mut crates) = result {
-                        crates.push(None);
+        let chars: Vec<char> = line.chars().collect();
+        let mut indexes = vec![];
+        let mut crates = vec![];
+        let mut position = 0;
+        while position < chars.len() {
+            match chars[position] {
+                ' ' | '\t' => position += 1,
+                '[' => {
+                    let end = match chars[position..].iter().position(|char| *char == ']') {
+                        Some(length) => position + length,
+                        None => return LineType::Other,
+                    };
+                    if end == position + 1 {
+                        return LineType::Other;
                     }
+                    let id: String = chars[position + 1..end].iter().collect();
+                    crates.push(((position + end) / 2, id));
+                    position = end + 1;
                 }
-                HeaderToken::Index(index) => {
-                    if result == LineType::Other {
-                        result = LineType::Index(index);
-                    } else if let LineType::Index(_) = result {
-                        result = LineType::Index(index);
+                '0'..='9' => {
+                    let length = chars[position..]
+                        .iter()
+                        .take_while(|char| char.is_ascii_digit())
+                        .count();
+                    let end = position + length - 1;
+                    let index: String = chars[position..=end].iter().collect();
+                    match index.parse::<usize>() {
+                        Ok(index) if index > 0 => indexes.push(((position + end) / 2, index)),
+                        _ => return LineType::Other,
                     }
+                    position = end + 1;
                 }
-                _ => {}
+                _ => return LineType::Other,
             }
-            matches!(chars.next(), Some(_))
-        } {}
-        result
+        }
+
+        match (crates.is_empty(), indexes.is_empty()) {
+            (false, true) => LineType::Crate(crates),
+            (true, false) => LineType::Index(indexes),
+            _ => LineType::Other,
+        }
     }
 }
 
@@ -275,36 +385,82 @@ mod test {
         assert_eq!(
             SupplyStacks::parse_header_line("[Z] [F]     [G] [Q] [F]            "),
             LineType::Crate(vec![
-                Some('Z'),
-                Some('F'),
-                None,
-                Some('G'),
-                Some('Q'),
-                Some('F'),
-                None,
-                None,
-                None
+                (1, "Z".to_string()),
+                (5, "F".to_string()),
+                (13, "G".to_string()),
+                (17, "Q".to_string()),
+                (21, "F".to_string()),
             ])
         );
         assert_eq!(
-            SupplyStacks::parse_header_line("[J]             [F] [M]            "),
+            SupplyStacks::parse_header_line("[J]             [F] [M]"),
             LineType::Crate(vec![
-                Some('J'),
-                None,
-                None,
-                None,
-                Some('F'),
-                Some('M'),
-                None,
-                None,
-                None
+                (1, "J".to_string()),
+                (17, "F".to_string()),
+                (21, "M".to_string()),
             ])
         );
         assert_eq!(
             SupplyStacks::parse_header_line(" 1   2   3   4   5   6   7   8   9 "),
-            LineType::Index(9)
+            LineType::Index((1..=9).map(|index| (index * 4 - 3, index)).collect())
+        );
+        assert_eq!(
+            SupplyStacks::parse_header_line(" 9  10  11"),
+            LineType::Index(vec![(1, 9), (4, 10), (8, 11)])
         );
         assert_eq!(SupplyStacks::parse_header_line(""), LineType::Empty);
+        assert_eq!(SupplyStacks::parse_header_line("   "), LineType::Empty);
+        assert_eq!(
+            SupplyStacks::parse_header_line("move 1 from 2 to 3"),
+            LineType::Other
+        );
+    }
+
+    #[test]
+    fn test_parse_wide_header() {
+        let header = r#"        [10]
+[a] [B] [C7]
+ 1   2   3"#
+            .to_string();
+        let mut lines = read_from_string(&header);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        assert_eq!(
+            supply_stacks.stacks,
+            vec![
+                Stack {
+                    crate_stack: vec![Crate::new("a")]
+                },
+                Stack {
+                    crate_stack: vec![Crate::new("B")]
+                },
+                Stack {
+                    crate_stack: vec![Crate::new("C7"), Crate::new("10")]
+                },
+            ]
+        );
+
+        let header = r#"                                    [x] [y] [z]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K] [L]
+ 1   2   3   4   5   6   7   8   9  10  11  12"#
+            .to_string();
+        let mut lines = read_from_string(&header);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        assert_eq!(supply_stacks.stacks.len(), 12);
+        assert_eq!(supply_stacks.top_of_each_stack(), "ABCDEFGHIxyz");
+
+        let rendered = supply_stacks.to_string();
+        let mut lines = read_from_string(&rendered);
+        assert_eq!(SupplyStacks::parse(&mut lines).stacks, supply_stacks.stacks);
+    }
+
+    #[test]
+    fn test_parse_header_sparse_indexes() {
+        let header = "[A]\n 1  1000000000000".to_string();
+        let mut lines = read_from_string(&header);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        assert_eq!(supply_stacks.stacks.len(), 2);
+        assert_eq!(supply_stacks.stacks[0].crate_stack, vec![Crate::new("A")]);
+        assert_eq!(supply_stacks.stacks[1].crate_stack, vec![]);
     }
 
     #[test]
@@ -327,14 +483,14 @@ mod test {
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'N' },
-                    Crate { id: 'B' },
-                    Crate { id: 'D' },
-                    Crate { id: 'T' },
-                    Crate { id: 'V' },
-                    Crate { id: 'G' },
-                    Crate { id: 'Z' },
-                    Crate { id: 'J' }
+                    Crate::new("N"),
+                    Crate::new("B"),
+                    Crate::new("D"),
+                    Crate::new("T"),
+                    Crate::new("V"),
+                    Crate::new("G"),
+                    Crate::new("Z"),
+                    Crate::new("J")
                 ]
             }
         );
@@ -343,13 +499,13 @@ mod test {
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'S' },
-                    Crate { id: 'R' },
-                    Crate { id: 'M' },
-                    Crate { id: 'D' },
-                    Crate { id: 'W' },
-                    Crate { id: 'P' },
-                    Crate { id: 'F' }
+                    Crate::new("S"),
+                    Crate::new("R"),
+                    Crate::new("M"),
+                    Crate::new("D"),
+                    Crate::new("W"),
+                    Crate::new("P"),
+                    Crate::new("F")
                 ]
             }
         );
@@ -358,11 +514,11 @@ mod test {
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'V' },
-                    Crate { id: 'C' },
-                    Crate { id: 'R' },
-                    Crate { id: 'S' },
-                    Crate { id: 'Z' }
+                    Crate::new("V"),
+                    Crate::new("C"),
+                    Crate::new("R"),
+                    Crate::new("S"),
+                    Crate::new("Z")
                 ]
             }
         );
@@ -371,10 +527,10 @@ mod test {
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'Z' },
-                    Crate { id: 'B' },
-                    Crate { id: 'P' },
-                    Crate { id: 'N' }
+                    Crate::new("Z"),
+                    Crate::new("B"),
+                    Crate::new("P"),
+                    Crate::new("N")
                 ]
             }
         );
@@ -382,7 +538,7 @@ mod test {
         assert_eq!(
             *res,
             Stack {
-                crate_stack: vec![Crate { id: 'W' }, Crate { id: 'P' }, Crate { id: 'J' }]
+                crate_stack: vec![Crate::new("W"), Crate::new("P"), Crate::new("J")]
             }
         );
     }
@@ -401,19 +557,21 @@ move 1 from 1 to 2"#
             .to_string();
         let mut lines = read_from_string(&complete);
         let mut supply_stacks = SupplyStacks::parse(&mut lines);
-        supply_stacks.parse_move(&mut lines, &MoveMethod::Reorder);
+        supply_stacks
+            .parse_move(&mut lines, &MoveMethod::Reorder)
+            .unwrap();
         let res = supply_stacks.stacks.get(0).unwrap();
         assert_eq!(
             *res,
             Stack {
-                crate_stack: vec![Crate { id: 'C' }]
+                crate_stack: vec![Crate::new("C")]
             }
         );
         let res = supply_stacks.stacks.get(1).unwrap();
         assert_eq!(
             *res,
             Stack {
-                crate_stack: vec![Crate { id: 'M' },]
+                crate_stack: vec![Crate::new("M"),]
             }
         );
         let res = supply_stacks.stacks.get(2).unwrap();
@@ -421,10 +579,10 @@ move 1 from 1 to 2"#
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'P' },
-                    Crate { id: 'D' },
-                    Crate { id: 'N' },
-                    Crate { id: 'Z' }
+                    Crate::new("P"),
+                    Crate::new("D"),
+                    Crate::new("N"),
+                    Crate::new("Z")
                 ]
             }
         );
@@ -446,19 +604,21 @@ move 1 from 1 to 2"#
             .to_string();
         let mut lines = read_from_string(&complete);
         let mut supply_stacks = SupplyStacks::parse(&mut lines);
-        supply_stacks.parse_move(&mut lines, &MoveMethod::KeepOrder);
+        supply_stacks
+            .parse_move(&mut lines, &MoveMethod::KeepOrder)
+            .unwrap();
         let res = supply_stacks.stacks.get(0).unwrap();
         assert_eq!(
             *res,
             Stack {
-                crate_stack: vec![Crate { id: 'M' }]
+                crate_stack: vec![Crate::new("M")]
             }
         );
         let res = supply_stacks.stacks.get(1).unwrap();
         assert_eq!(
             *res,
             Stack {
-                crate_stack: vec![Crate { id: 'C' },]
+                crate_stack: vec![Crate::new("C"),]
             }
         );
         let res = supply_stacks.stacks.get(2).unwrap();
@@ -466,14 +626,122 @@ move 1 from 1 to 2"#
             *res,
             Stack {
                 crate_stack: vec![
-                    Crate { id: 'P' },
-                    Crate { id: 'Z' },
-                    Crate { id: 'N' },
-                    Crate { id: 'D' }
+                    Crate::new("P"),
+                    Crate::new("Z"),
+                    Crate::new("N"),
+                    Crate::new("D")
                 ]
             }
         );
 
         assert_eq!("MCD".to_string(), supply_stacks.top_of_each_stack());
     }
+
+    #[test]
+    fn test_same_stack_move() {
+        let header = r#"[C]
+[B]
+[A]
+ 1 "#
+        .to_string();
+        let mut lines = read_from_string(&header);
+        let mut supply_stacks = SupplyStacks::parse(&mut lines);
+        let expected = supply_stacks.stacks.clone();
+        for move_method in [MoveMethod::Reorder, MoveMethod::KeepOrder] {
+            supply_stacks.apply_move(3, 0, 0, &move_method).unwrap();
+            assert_eq!(supply_stacks.stacks, expected);
+        }
+        assert!(supply_stacks.replay_to(0));
+        assert_eq!(supply_stacks.stacks, expected);
+    }
+
+    #[test]
+    fn test_move_errors() {
+        let complete = r#"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 4 from 1 to 3
+move 1 from 4 to 1
+jump 1 from 1 to 2
+move 1 from 0 to 2
+move 99999999999 from 1 to 1
+move 2 from 2 to 1"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let mut supply_stacks = SupplyStacks::parse(&mut lines);
+        let mut moves = read_from_string(&complete).skip(5);
+        let errors = supply_stacks.validate(&mut moves, &MoveMethod::Reorder);
+        assert_eq!(
+            errors,
+            vec![
+                MoveError {
+                    line: Some(7),
+                    kind: MoveErrorKind::NotEnoughCrates {
+                        stack: 1,
+                        requested: 4,
+                        available: 3
+                    }
+                },
+                MoveError {
+                    line: Some(8),
+                    kind: MoveErrorKind::UnknownStack(4)
+                },
+                MoveError {
+                    line: Some(9),
+                    kind: MoveErrorKind::InvalidInstruction("jump 1 from 1 to 2".to_string())
+                },
+                MoveError {
+                    line: Some(10),
+                    kind: MoveErrorKind::InvalidInstruction("move 1 from 0 to 2".to_string())
+                },
+                MoveError {
+                    line: Some(11),
+                    kind: MoveErrorKind::InvalidInstruction(
+                        "move 99999999999 from 1 to 1".to_string()
+                    )
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "line 7: cannot move 4 crates from stack 1 holding 3"
+        );
+        assert_eq!(supply_stacks.step(), 0);
+        assert_eq!(supply_stacks.top_of_each_stack(), "NDP");
+
+        let error = supply_stacks
+            .parse_move(&mut lines, &MoveMethod::Reorder)
+            .unwrap_err();
+        assert_eq!(error.line, Some(7));
+        assert_eq!(supply_stacks.step(), 1);
+        assert_eq!(supply_stacks.top_of_each_stack(), "DCP");
+
+        assert_eq!(
+            supply_stacks.apply_move(1, 2, 0, &MoveMethod::Reorder),
+            Ok(())
+        );
+        assert_eq!(supply_stacks.top_of_each_stack(), "PC_");
+    }
+
+    #[test]
+    fn test_line_numbers_count_unreadable_lines() {
+        let unreadable = || Err(io::Error::new(io::ErrorKind::InvalidData, "bad line"));
+        let mut lines = vec![
+            unreadable(),
+            Ok("[A]".to_string()),
+            Ok(" 1 ".to_string()),
+            Ok("".to_string()),
+            Ok("move 1 from 1 to 1".to_string()),
+            unreadable(),
+            Ok("move 2 from 1 to 1".to_string()),
+        ]
+        .into_iter();
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        let errors = supply_stacks.validate(&mut lines, &MoveMethod::Reorder);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, Some(7));
+    }
 }
@@ -0,0 +1,258 @@
+use super::{Move, MoveError, MoveMethod, Stack, SupplyStacks};
+
+enum Combination {
+    Cancel,
+    Merged(Move),
+    Unchanged,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OptimizedMoves {
+    pub moves: Vec<Move>,
+    pub removed: usize,
+    pub equivalent: bool,
+}
+
+impl Move {
+    fn is_noop(&self) -> bool {
+        self.count == 0 || self.from == self.to
+    }
+
+    fn combine(&self, next: &Move, move_method: &MoveMethod) -> Combination {
+        if self.count == next.count && self.from == next.to && self.to == next.from {
+            return Combination::Cancel;
+        }
+        if *move_method == MoveMethod::Reorder && self.from == next.from && self.to == next.to {
+            return Combination::Merged(Move {
+                count: self.count + next.count,
+                from: self.from,
+                to: self.to,
+            });
+        }
+        // Carrying the same crates twice in a row only counts where they end up
+        if self.count == next.count
+            && self.to == next.from
+            && (*move_method == MoveMethod::KeepOrder || self.count == 1)
+        {
+            return Combination::Merged(Move {
+                count: self.count,
+                from: self.from,
+                to: next.to,
+            });
+        }
+        Combination::Unchanged
+    }
+}
+
+impl SupplyStacks {
+    pub fn optimize_moves(
+        &self,
+        moves: &[Move],
+        move_method: &MoveMethod,
+    ) -> Result<OptimizedMoves, MoveError> {
+        let expected = self.simulate_moves(moves, move_method)?;
+
+        // states[i] holds the stacks after optimized[..i], to check each rewrite against the original
+        let mut start = self.clone();
+        start.forget_journal();
+        let mut optimized: Vec<Move> = vec![];
+        let mut states = vec![start.clone()];
+        let mut original = start;
+        for crane_move in moves {
+            original.apply_move(
+                crane_move.count,
+                crane_move.from,
+                crane_move.to,
+                move_method,
+            )?;
+            original.forget_journal();
+            let mut popped = vec![];
+            let mut pushed = false;
+            let mut current = *crane_move;
+            loop {
+                if current.is_noop() {
+                    break;
+                }
+                let combination = match optimized.last() {
+                    Some(previous) => previous.combine(&current, move_method),
+                    None => Combination::Unchanged,
+                };
+                match combination {
+                    Combination::Cancel => {
+                        popped.extend(optimized.pop());
+                        states.pop();
+                        break;
+                    }
+                    Combination::Merged(merged) => {
+                        popped.extend(optimized.pop());
+                        states.pop();
+                        current = merged;
+                    }
+                    Combination::Unchanged => {
+                        Self::push_move(&mut optimized, &mut states, current, move_method);
+                        pushed = true;
+                        break;
+                    }
+                }
+            }
+            // A rewrite that changes the result is dropped, the move is kept as written
+            if states.last().map(|state| &state.stacks) != Some(&original.stacks) {
+                if pushed {
+                    optimized.pop();
+                    states.pop();
+                }
+                for previous in popped.into_iter().rev() {
+                    Self::push_move(&mut optimized, &mut states, previous, move_method);
+                }
+                Self::push_move(&mut optimized, &mut states, *crane_move, move_method);
+            }
+        }
+
+        let equivalent = self.simulate_moves(&optimized, move_method).ok() == Some(expected);
+        Ok(OptimizedMoves {
+            removed: moves.len() - optimized.len(),
+            moves: optimized,
+            equivalent,
+        })
+    }
+
+    fn push_move(
+        optimized: &mut Vec<Move>,
+        states: &mut Vec<SupplyStacks>,
+        crane_move: Move,
+        move_method: &MoveMethod,
+    ) {
+        let mut state = states.last().unwrap().clone();
+        // An invalid rewrite leaves the state unchanged and fails the comparison
+        let _ = state.apply_move(
+            crane_move.count,
+            crane_move.from,
+            crane_move.to,
+            move_method,
+        );
+        state.forget_journal();
+        optimized.push(crane_move);
+        states.push(state);
+    }
+
+    fn simulate_moves(
+        &self,
+        moves: &[Move],
+        move_method: &MoveMethod,
+    ) -> Result<Vec<Stack>, MoveError> {
+        let mut supply_stacks = self.clone();
+        for crane_move in moves {
+            supply_stacks.apply_move(
+                crane_move.count,
+                crane_move.from,
+                crane_move.to,
+                move_method,
+            )?;
+        }
+        Ok(supply_stacks.stacks)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    fn sample() -> (SupplyStacks, Vec<Move>) {
+        let complete = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 1 from 1 to 2
+move 1 from 2 to 1
+move 2 from 1 to 3
+move 1 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+move 0 from 3 to 1"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        (supply_stacks, Move::parse_all(&mut lines))
+    }
+
+    #[test]
+    fn test_optimize_reorder() {
+        let (supply_stacks, moves) = sample();
+        let optimized = supply_stacks
+            .optimize_moves(&moves, &MoveMethod::Reorder)
+            .unwrap();
+        assert!(optimized.equivalent);
+        assert_eq!(optimized.removed, 4);
+        assert_eq!(
+            Move::format_all(&optimized.moves),
+            r#"move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#
+        );
+    }
+
+    #[test]
+    fn test_optimize_keep_order() {
+        let (supply_stacks, moves) = sample();
+        let optimized = supply_stacks
+            .optimize_moves(&moves, &MoveMethod::KeepOrder)
+            .unwrap();
+        assert!(optimized.equivalent);
+        assert_eq!(optimized.removed, 3);
+
+        let moves = vec![
+            Move {
+                count: 2,
+                from: 1,
+                to: 0,
+            },
+            Move {
+                count: 2,
+                from: 0,
+                to: 2,
+            },
+            Move {
+                count: 1,
+                from: 2,
+                to: 2,
+            },
+        ];
+        let optimized = supply_stacks
+            .optimize_moves(&moves, &MoveMethod::KeepOrder)
+            .unwrap();
+        assert_eq!(Move::format_all(&optimized.moves), "move 2 from 2 to 3");
+        assert_eq!(optimized.removed, 2);
+        let optimized = supply_stacks
+            .optimize_moves(&moves, &MoveMethod::Reorder)
+            .unwrap();
+        assert_eq!(optimized.removed, 1);
+
+        let moves = vec![Move {
+            count: 3,
+            from: 1,
+            to: 1,
+        }];
+        let optimized = supply_stacks
+            .optimize_moves(&moves, &MoveMethod::Reorder)
+            .unwrap();
+        assert!(optimized.equivalent);
+        assert!(optimized.moves.is_empty());
+    }
+
+    #[test]
+    fn test_optimize_invalid() {
+        let (supply_stacks, _) = sample();
+        let moves = vec![Move {
+            count: 5,
+            from: 0,
+            to: 1,
+        }];
+        assert!(supply_stacks
+            .optimize_moves(&moves, &MoveMethod::Reorder)
+            .is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use super::{Crane, Crate, Move, PlanError, Stack, SupplyStacks};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const MAX_PLANNER_STATES: usize = 1_000_000;
+
+type Layout = Vec<Vec<String>>;
+
+impl SupplyStacks {
+    fn layout(&self) -> Layout {
+        self.stacks
+            .iter()
+            .map(|stack| {
+                stack
+                    .crate_stack
+                    .iter()
+                    .map(|crate_element| crate_element.id.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn from_layout(layout: &Layout) -> Self {
+        let stacks = layout
+            .iter()
+            .map(|ids| Stack {
+                crate_stack: ids.iter().map(|id| Crate::new(id)).collect(),
+            })
+            .collect();
+        SupplyStacks {
+            stacks,
+            journal: vec![],
+            position: 0,
+            lines_read: 0,
+        }
+    }
+
+    // Every move changes exactly one source and one destination stack
+    fn remaining_moves(layout: &Layout, target: &Layout) -> usize {
+        let mut sources = 0;
+        let mut destinations = 0;
+        for (current, wanted) in layout.iter().zip(target.iter()) {
+            let common = current
+                .iter()
+                .zip(wanted.iter())
+                .take_while(|(current, wanted)| current == wanted)
+                .count();
+            if current.len() > common {
+                sources += 1;
+            }
+            if wanted.len() > common {
+                destinations += 1;
+            }
+        }
+        sources.max(destinations)
+    }
+
+    pub fn plan<C: Crane + ?Sized>(
+        &self,
+        target: &SupplyStacks,
+        crane: &C,
+    ) -> Result<Vec<Move>, PlanError> {
+        self.plan_within(target, crane, MAX_PLANNER_STATES)
+    }
+
+    pub fn plan_within<C: Crane + ?Sized>(
+        &self,
+        target: &SupplyStacks,
+        crane: &C,
+        max_states: usize,
+    ) -> Result<Vec<Move>, PlanError> {
+        let start = self.layout();
+        let target = target.layout();
+        let mut start_crates: Vec<&String> = start.iter().flatten().collect();
+        let mut target_crates: Vec<&String> = target.iter().flatten().collect();
+        start_crates.sort();
+        target_crates.sort();
+        if start.len() != target.len() || start_crates != target_crates {
+            return Err(PlanError::Unreachable);
+        }
+
+        let mut queue = BinaryHeap::new();
+        let mut best: HashMap<Layout, usize> = HashMap::new();
+        let mut came_from: HashMap<Layout, (Layout, Move)> = HashMap::new();
+        queue.push(Reverse((
+            Self::remaining_moves(&start, &target),
+            0,
+            start.clone(),
+        )));
+        best.insert(start, 0);
+
+        while let Some(Reverse((_, cost, layout))) = queue.pop() {
+            if layout == target {
+                let mut moves = vec![];
+                let mut current = layout;
+                while let Some((previous, crane_move)) = came_from.get(&current) {
+                    moves.push(*crane_move);
+                    current = previous.clone();
+                }
+                moves.reverse();
+                return Ok(moves);
+            }
+            if best.len() > max_states {
+                return Err(PlanError::Exhausted);
+            }
+            if best.get(&layout).is_some_and(|best| *best < cost) {
+                continue;
+            }
+
+            for from in 0..layout.len() {
+                for to in 0..layout.len() {
+                    if from == to {
+                        continue;
+                    }
+                    for count in 1..=layout[from].len() {
+                        let crane_move = Move {
+                            count: u32::try_from(count).unwrap(),
+                            from,
+                            to,
+                        };
+                        let mut supply_stacks = Self::from_layout(&layout);
+                        if crane.execute(&mut supply_stacks, &crane_move).is_err() {
+                            continue;
+                        }
+                        let next = supply_stacks.layout();
+                        if best.get(&next).is_some_and(|best| *best <= cost + 1) {
+                            continue;
+                        }
+                        best.insert(next.clone(), cost + 1);
+                        came_from.insert(next.clone(), (layout.clone(), crane_move));
+                        let estimate = cost + 1 + Self::remaining_moves(&next, &target);
+                        queue.push(Reverse((estimate, cost + 1, next)));
+                    }
+                }
+            }
+        }
+        Err(PlanError::Unreachable)
+    }
+}
+
+impl Move {
+    pub fn format_all(moves: &[Move]) -> String {
+        moves
+            .iter()
+            .map(|crane_move| crane_move.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day5::{CapacityCrane, MoveMethod};
+    use crate::testtools::*;
+
+    fn parse(header: &str) -> SupplyStacks {
+        let header = header.to_string();
+        let mut lines = read_from_string(&header);
+        SupplyStacks::parse(&mut lines)
+    }
+
+    #[test]
+    fn test_plan() {
+        let start = parse(
+            r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3"#,
+        );
+        let target = parse(
+            r#"        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3"#,
+        );
+
+        assert_eq!(start.plan(&start, &MoveMethod::Reorder), Ok(vec![]));
+
+        for crane in [
+            &MoveMethod::Reorder as &dyn Crane,
+            &MoveMethod::KeepOrder,
+            &CapacityCrane::new(1),
+        ] {
+            let moves = start.plan(&target, crane).unwrap();
+            let listing = Move::format_all(&moves);
+            let mut lines = read_from_string(&listing);
+            let mut supply_stacks = start.clone();
+            supply_stacks.parse_move(&mut lines, crane).unwrap();
+            assert_eq!(supply_stacks.layout(), target.layout());
+            assert!(moves.len() <= 4);
+        }
+
+        let moves = start.plan(&target, &MoveMethod::Reorder).unwrap();
+        assert_eq!(
+            Move::format_all(&moves),
+            "move 1 from 2 to 3\nmove 2 from 1 to 3\nmove 1 from 2 to 1"
+        );
+        assert_eq!(
+            start.plan(&target, &MoveMethod::KeepOrder).unwrap().len(),
+            4
+        );
+        assert_eq!(
+            start.plan(&target, &CapacityCrane::new(1)).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_plan_impossible() {
+        let start = parse(
+            r#"[A] [B]
+ 1   2"#,
+        );
+        let target = parse(
+            r#"[A] [C]
+ 1   2"#,
+        );
+        assert_eq!(
+            start.plan(&target, &MoveMethod::KeepOrder),
+            Err(PlanError::Unreachable)
+        );
+    }
+
+    #[test]
+    fn test_plan_exhausted() {
+        let start = parse(
+            r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3"#,
+        );
+        let target = parse(
+            r#"[D] [C] [Z]
+[P] [N] [M]
+ 1   2   3"#,
+        );
+        assert!(start.plan(&target, &MoveMethod::KeepOrder).is_ok());
+        assert_eq!(
+            start.plan_within(&target, &MoveMethod::KeepOrder, 5),
+            Err(PlanError::Exhausted)
+        );
+    }
+}
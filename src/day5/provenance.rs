@@ -0,0 +1,185 @@
+use super::{Crate, Move, SupplyStacks};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CratePosition {
+    pub stack: usize,
+    pub height: usize,
+}
+
+impl fmt::Display for CratePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "stack {} height {}", self.stack + 1, self.height + 1)
+    }
+}
+
+impl SupplyStacks {
+    pub(super) fn assign_origins(&mut self) {
+        for (stack, stack_element) in self.stacks.iter_mut().enumerate() {
+            for (height, crate_element) in stack_element.crate_stack.iter_mut().enumerate() {
+                crate_element.origin = CratePosition { stack, height };
+            }
+        }
+    }
+
+    fn crate_at_origin(&self, origin: CratePosition) -> Option<&Crate> {
+        self.stacks
+            .iter()
+            .flat_map(|stack| stack.crate_stack.iter())
+            .find(|crate_element| crate_element.origin == origin)
+    }
+
+    pub fn find_crate(&self, origin: CratePosition) -> Option<CratePosition> {
+        for (stack, stack_element) in self.stacks.iter().enumerate() {
+            for (height, crate_element) in stack_element.crate_stack.iter().enumerate() {
+                if crate_element.origin == origin {
+                    return Some(CratePosition { stack, height });
+                }
+            }
+        }
+        None
+    }
+
+    pub fn crate_history(&self, origin: CratePosition) -> Vec<Move> {
+        self.crate_at_origin(origin)
+            .map(|crate_element| crate_element.history().to_vec())
+            .unwrap_or_default()
+    }
+
+    pub fn distance_travelled(&self, origin: CratePosition) -> usize {
+        self.crate_history(origin)
+            .iter()
+            .fold(0, |accu, crane_move| {
+                accu + crane_move.from.abs_diff(crane_move.to)
+            })
+    }
+
+    pub fn unmoved_crates(&self) -> Vec<CratePosition> {
+        let mut result: Vec<CratePosition> = self
+            .stacks
+            .iter()
+            .flat_map(|stack| stack.crate_stack.iter())
+            .filter(|crate_element| crate_element.history().is_empty())
+            .map(|crate_element| crate_element.origin)
+            .collect();
+        result.sort();
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day5::MoveMethod;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_provenance() {
+        let complete = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let mut supply_stacks = SupplyStacks::parse(&mut lines);
+        supply_stacks
+            .parse_move(&mut lines, &MoveMethod::Reorder)
+            .unwrap();
+
+        let crate_n = CratePosition {
+            stack: 0,
+            height: 1,
+        };
+        assert_eq!(
+            supply_stacks.find_crate(crate_n),
+            Some(CratePosition {
+                stack: 2,
+                height: 2
+            })
+        );
+        assert_eq!(
+            supply_stacks.find_crate(crate_n).unwrap().to_string(),
+            "stack 3 height 3"
+        );
+        assert_eq!(supply_stacks.distance_travelled(crate_n), 2);
+
+        let crate_d = CratePosition {
+            stack: 1,
+            height: 2,
+        };
+        let history: Vec<String> = supply_stacks
+            .crate_history(crate_d)
+            .iter()
+            .map(|crane_move| crane_move.to_string())
+            .collect();
+        assert_eq!(history, vec!["move 1 from 2 to 1", "move 3 from 1 to 3"]);
+        assert_eq!(supply_stacks.distance_travelled(crate_d), 3);
+
+        assert_eq!(
+            supply_stacks.unmoved_crates(),
+            vec![CratePosition {
+                stack: 2,
+                height: 0
+            }]
+        );
+        assert_eq!(
+            supply_stacks.find_crate(CratePosition {
+                stack: 2,
+                height: 1
+            }),
+            None
+        );
+
+        supply_stacks.replay_to(1);
+        assert_eq!(supply_stacks.unmoved_crates().len(), 5);
+        assert_eq!(supply_stacks.distance_travelled(crate_d), 1);
+    }
+
+    #[test]
+    fn test_provenance_without_journal() {
+        let complete = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#
+            .to_string();
+        let mut lines = read_from_string(&complete);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        let moves = Move::parse_all(&mut lines);
+        let crate_d = CratePosition {
+            stack: 1,
+            height: 2,
+        };
+
+        let last = supply_stacks
+            .steps(&moves, &MoveMethod::Reorder)
+            .last()
+            .unwrap()
+            .unwrap();
+        assert!(last.journal().is_empty());
+        assert_eq!(last.crate_history(crate_d), moves[..2].to_vec());
+        assert_eq!(last.distance_travelled(crate_d), 3);
+        assert_eq!(last.unmoved_crates().len(), 1);
+
+        // A new move after undo keeps the history of the crates it does not carry
+        let mut supply_stacks = last;
+        supply_stacks
+            .apply_move(1, 2, 1, &MoveMethod::Reorder)
+            .unwrap();
+        supply_stacks.undo();
+        supply_stacks
+            .apply_move(1, 0, 1, &MoveMethod::Reorder)
+            .unwrap();
+        assert_eq!(supply_stacks.distance_travelled(crate_d), 3);
+        assert_eq!(supply_stacks.crate_history(crate_d).len(), 2);
+    }
+}
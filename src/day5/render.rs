@@ -0,0 +1,175 @@
+use super::SupplyStacks;
+use std::fmt;
+
+const COLOR_FROM: &str = "\x1b[33m";
+const COLOR_TO: &str = "\x1b[1;32m";
+const COLOR_RESET: &str = "\x1b[0m";
+
+fn center(text: &str, width: usize) -> String {
+    let left = (width - text.chars().count()) / 2;
+    format!("{:left$}{:<right$}", "", text, right = width - left)
+}
+
+impl SupplyStacks {
+    fn column_width(&self) -> usize {
+        let label_width = self
+            .stacks
+            .iter()
+            .flat_map(|stack| stack.crate_stack.iter())
+            .map(|crate_element| crate_element.id.chars().count() + 2)
+            .max()
+            .unwrap_or(3);
+        let index_width = self.stacks.len().to_string().len();
+        label_width.max(index_width).max(3)
+    }
+
+    fn render_with(&self, style: &dyn Fn(usize, Option<usize>) -> Option<&'static str>) -> String {
+        let width = self.column_width();
+        let height = self
+            .stacks
+            .iter()
+            .map(|stack| stack.crate_stack.len())
+            .max()
+            .unwrap_or(0);
+        let styled = |stack_index: usize, level: Option<usize>, text: String| {
+            if let Some(color) = style(stack_index, level) {
+                format!("{}{}{}", color, text, COLOR_RESET)
+            } else {
+                text
+            }
+        };
+
+        let mut lines = vec![];
+        for level in (0..height).rev() {
+            let cells: Vec<String> = self
+                .stacks
+                .iter()
+                .enumerate()
+                .map(|(stack_index, stack)| match stack.crate_stack.get(level) {
+                    Some(crate_element) => styled(
+                        stack_index,
+                        Some(level),
+                        center(&format!("[{}]", crate_element.id), width),
+                    ),
+                    None => " ".repeat(width),
+                })
+                .collect();
+            lines.push(cells.join(" "));
+        }
+        let indexes: Vec<String> = (0..self.stacks.len())
+            .map(|stack_index| {
+                styled(
+                    stack_index,
+                    None,
+                    center(&(stack_index + 1).to_string(), width),
+                )
+            })
+            .collect();
+        lines.push(indexes.join(" "));
+        lines.join("\n")
+    }
+
+    pub fn to_compact_string(&self) -> String {
+        let stacks: Vec<String> = self
+            .stacks
+            .iter()
+            .enumerate()
+            .map(|(stack_index, stack)| {
+                let crates: String = stack
+                    .crate_stack
+                    .iter()
+                    .map(|crate_element| format!("[{}]", crate_element.id))
+                    .collect();
+                format!("{}:{}", stack_index + 1, crates)
+            })
+            .collect();
+        stacks.join(" ")
+    }
+
+    pub fn render_colored(&self) -> String {
+        let last_entry = self.journal().last();
+        self.render_with(&|stack_index, level| {
+            let entry = last_entry?;
+            if stack_index == entry.crane_move.from {
+                Some(COLOR_FROM)
+            } else if stack_index == entry.crane_move.to {
+                let placed_from = self.stacks[stack_index].crate_stack.len() - entry.placed().len();
+                match level {
+                    Some(level) if level < placed_from => None,
+                    _ => Some(COLOR_TO),
+                }
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl fmt::Display for SupplyStacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render_with(&|_, _| None))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day5::MoveMethod;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_display_round_trip() {
+        let header = r#"[J]             [F] [M]            
+[Z] [F]     [G] [Q] [F]            
+[G] [P]     [H] [Z] [S] [Q]        
+[V] [W] [Z] [P] [D] [G] [P]        
+[T] [D] [S] [Z] [N] [W] [B] [N]    
+[D] [M] [R] [J] [J] [P] [V] [P] [J]
+[B] [R] [C] [T] [C] [V] [C] [B] [P]
+[N] [S] [V] [R] [T] [N] [G] [Z] [W]
+ 1   2   3   4   5   6   7   8   9 "#
+            .to_string();
+        let mut lines = read_from_string(&header);
+        let supply_stacks = SupplyStacks::parse(&mut lines);
+        let rendered = supply_stacks.to_string();
+        assert_eq!(rendered, header);
+
+        let mut lines = read_from_string(&rendered);
+        assert_eq!(SupplyStacks::parse(&mut lines).stacks, supply_stacks.stacks);
+    }
+
+    #[test]
+    fn test_compact_and_colored() {
+        let header = r#"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 "#
+            .to_string();
+        let mut lines = read_from_string(&header);
+        let mut supply_stacks = SupplyStacks::parse(&mut lines);
+        assert_eq!(
+            supply_stacks.to_compact_string(),
+            "1:[Z][N] 2:[M][C][D] 3:[P]"
+        );
+        assert_eq!(supply_stacks.render_colored(), header);
+
+        supply_stacks
+            .apply_move(2, 1, 0, &MoveMethod::Reorder)
+            .unwrap();
+        assert_eq!(
+            supply_stacks.to_compact_string(),
+            "1:[Z][N][D][C] 2:[M] 3:[P]"
+        );
+        assert_eq!(
+            supply_stacks.render_colored(),
+            [
+                "\x1b[1;32m[C]\x1b[0m        ",
+                "\x1b[1;32m[D]\x1b[0m        ",
+                "[N]        ",
+                "[Z] \x1b[33m[M]\x1b[0m [P]",
+                "\x1b[1;32m 1 \x1b[0m \x1b[33m 2 \x1b[0m  3 ",
+            ]
+            .join("\n")
+        );
+    }
+}
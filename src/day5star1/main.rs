@@ -4,6 +4,9 @@ fn main() {
     let mut lines = io::stdin().lines();
 
     let mut supply_stacks = SupplyStacks::parse(&mut lines);
-    supply_stacks.parse_move(&mut lines, &MoveMethod::Reorder);
+    if let Err(error) = supply_stacks.parse_move(&mut lines, &MoveMethod::Reorder) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
     println!("Top of each stack = {}", supply_stacks.top_of_each_stack());
 }
@@ -0,0 +1,253 @@
+use super::{MarkerDetector, MarkerKind};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub kind: MarkerKind,
+    pub offset: u64,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn length(&self) -> usize {
+        self.payload.len()
+    }
+}
+
+impl fmt::Display for MarkerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkerKind::StartOfPacket => write!(f, "packet"),
+            MarkerKind::StartOfMessage => write!(f, "message"),
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<8}{:>10}{:>8}  {}",
+            self.kind.to_string(),
+            self.offset,
+            self.length(),
+            self.payload.escape_ascii()
+        )
+    }
+}
+
+pub fn format_frames(frames: &[Frame]) -> String {
+    let mut table = format!("{:<8}{:>10}{:>8}  payload", "kind", "offset", "length");
+    for frame in frames {
+        table.push('\n');
+        table.push_str(&frame.to_string());
+    }
+    table
+}
+
+// Frames start right after a marker and stop where a later marker starts,
+// bytes before the first marker are dropped
+#[derive(Debug, Clone)]
+pub struct Framer {
+    detector: MarkerDetector,
+    packet_size: usize,
+    message_size: usize,
+    current: Option<Frame>,
+    // Closed frames a longer marker ending later could still reach back into
+    closed: Vec<Frame>,
+}
+
+impl Framer {
+    pub fn new() -> Self {
+        Framer::with_sizes(4, 14)
+    }
+
+    pub fn with_sizes(packet_size: usize, message_size: usize) -> Self {
+        Framer {
+            detector: MarkerDetector::with_sizes(packet_size, message_size),
+            packet_size,
+            message_size,
+            current: None,
+            closed: vec![],
+        }
+    }
+
+    fn marker_size(&self, kind: MarkerKind) -> u64 {
+        let size = match kind {
+            MarkerKind::StartOfPacket => self.packet_size,
+            MarkerKind::StartOfMessage => self.message_size,
+        };
+        u64::try_from(size).unwrap()
+    }
+
+    // Markers ending after `offset` start at `earliest` or later, frames ending before are final
+    fn release(&mut self, offset: u64, frames: &mut Vec<Frame>) {
+        let longest = self
+            .marker_size(MarkerKind::StartOfPacket)
+            .max(self.marker_size(MarkerKind::StartOfMessage));
+        let earliest = (offset + 1).saturating_sub(longest);
+        let done = self
+            .closed
+            .iter()
+            .take_while(|frame| frame.offset + u64::try_from(frame.length()).unwrap() <= earliest)
+            .count();
+        frames.extend(self.closed.drain(..done));
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        let chunk_start = self.detector.offset();
+        let mut frames = vec![];
+        let mut consumed = 0;
+        let events = self.detector.push(bytes);
+        for (index, event) in events.iter().enumerate() {
+            // A message marker ending on the same byte as a packet marker takes over its frame
+            if events
+                .get(index + 1)
+                .is_some_and(|next| next.offset == event.offset)
+            {
+                continue;
+            }
+            let end = usize::try_from(event.offset - chunk_start).unwrap();
+            let next = Frame {
+                kind: event.kind,
+                offset: event.offset,
+                payload: vec![],
+            };
+            if let Some(mut frame) = self.current.replace(next) {
+                frame.payload.extend_from_slice(&bytes[consumed..end]);
+                self.closed.push(frame);
+            }
+            consumed = end;
+            let marker_start = event.offset.saturating_sub(self.marker_size(event.kind));
+            for frame in &mut self.closed {
+                let length = marker_start.saturating_sub(frame.offset);
+                frame.payload.truncate(usize::try_from(length).unwrap());
+            }
+            self.release(event.offset, &mut frames);
+        }
+        if let Some(frame) = &mut self.current {
+            frame.payload.extend_from_slice(&bytes[consumed..]);
+        }
+        self.release(self.detector.offset(), &mut frames);
+        frames
+    }
+
+    pub fn finish(self) -> Vec<Frame> {
+        let mut frames = self.closed;
+        frames.extend(self.current);
+        frames
+    }
+}
+
+impl Default for Framer {
+    fn default() -> Self {
+        Framer::new()
+    }
+}
+
+pub fn split_frames(datastream: &[u8], packet_size: usize, message_size: usize) -> Vec<Frame> {
+    let mut framer = Framer::with_sizes(packet_size, message_size);
+    let mut frames = framer.push(datastream);
+    frames.extend(framer.finish());
+    frames
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn summary(frames: &[Frame]) -> Vec<(MarkerKind, u64, String)> {
+        frames
+            .iter()
+            .map(|frame| {
+                (
+                    frame.kind,
+                    frame.offset,
+                    String::from_utf8(frame.payload.clone()).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_frames() {
+        let frames = split_frames(b"aaabcdbbefghijklmnopqrs", 4, 8);
+        assert_eq!(
+            summary(&frames),
+            vec![
+                (MarkerKind::StartOfPacket, 6, "b".to_string()),
+                (MarkerKind::StartOfPacket, 11, "".to_string()),
+                (MarkerKind::StartOfMessage, 15, "".to_string()),
+                (MarkerKind::StartOfPacket, 19, "".to_string()),
+                (MarkerKind::StartOfMessage, 23, "".to_string()),
+            ]
+        );
+        assert_eq!(frames[0].length(), 1);
+        assert_eq!(split_frames(b"aaaa", 4, 14), vec![]);
+    }
+
+    #[test]
+    fn test_frame_boundary() {
+        let frames = split_frames(b"abcdxxxxwxyzxxxx", 4, 14);
+        assert_eq!(
+            summary(&frames),
+            vec![
+                (MarkerKind::StartOfPacket, 4, "xxxx".to_string()),
+                (MarkerKind::StartOfPacket, 12, "xxxx".to_string()),
+            ]
+        );
+        // Packet frames inside the message marker ending at 12 keep none of its bytes
+        let frames = split_frames(b"aabbxxcdxyzuvw", 2, 6);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.offset, frame.length()))
+                .collect::<Vec<(u64, usize)>>(),
+            vec![(3, 0), (5, 0), (7, 0), (9, 0), (11, 0), (12, 0), (13, 1)]
+        );
+    }
+
+    #[test]
+    fn test_framer_chunks() {
+        let datastream = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let expected = split_frames(datastream, 4, 14);
+        for chunk_size in [1, 2, 5] {
+            let mut framer = Framer::new();
+            let mut frames: Vec<Frame> = datastream
+                .chunks(chunk_size)
+                .flat_map(|chunk| framer.push(chunk))
+                .collect();
+            frames.extend(framer.finish());
+            assert_eq!(frames, expected);
+        }
+        // No payload byte belongs to a later marker
+        for (index, frame) in expected.iter().enumerate() {
+            let end = frame.offset + u64::try_from(frame.length()).unwrap();
+            for later in &expected[index + 1..] {
+                let marker_size = match later.kind {
+                    MarkerKind::StartOfPacket => 4,
+                    MarkerKind::StartOfMessage => 14,
+                };
+                assert!(frame.length() == 0 || end <= later.offset - marker_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_framer_default() {
+        let datastream = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let mut framer = Framer::default();
+        let mut frames = framer.push(datastream);
+        frames.extend(framer.finish());
+        assert_eq!(frames, split_frames(datastream, 4, 14));
+    }
+
+    #[test]
+    fn test_format_frames() {
+        let frames = split_frames(b"abcdxy\n", 4, 14);
+        assert_eq!(
+            format_frames(&frames),
+            "kind        offset  length  payload\npacket           4       3  xy\\n"
+        );
+    }
+}
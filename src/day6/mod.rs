@@ -1,4 +1,13 @@
+mod frame;
+mod report;
+mod stream;
+
 use std::io;
+use stream::MarkerWindow;
+
+pub use frame::{format_frames, split_frames, Frame, Framer};
+pub use report::{report_markers, DatastreamReport, InputMode, MarkerStatistics, MarkerSummary};
+pub use stream::{MarkerDetector, MarkerEvent, MarkerEvents, MarkerKind};
 
 pub fn find_start_of_packet(
     lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
@@ -18,27 +27,20 @@ fn find_start_of_sequence(
 ) -> Option<usize> {
     let line = lines.next();
     if let Some(Ok(message)) = line {
-        let message_len = message.len();
-        for position in 0..message_len - sequence_size {
-            let subpacket = &message[position..position + sequence_size];
-            let mut good_start = true;
-            'pattern_search: for start in 0..sequence_size - 1 {
-                for index in start + 1..sequence_size {
-                    if subpacket.chars().nth(index) == subpacket.chars().nth(start) {
-                        good_start = false;
-                        break 'pattern_search;
-                    }
-                }
-            }
-
-            if good_start {
-                return Some(position + sequence_size);
-            }
-        }
+        return find_marker(message.as_bytes(), sequence_size);
     }
     None
 }
 
+pub fn find_marker(message: &[u8], sequence_size: usize) -> Option<usize> {
+    let mut window = MarkerWindow::new(sequence_size);
+    message
+        .iter()
+        .enumerate()
+        .find(|(position, byte)| window.push(*position as u64, **byte))
+        .map(|(position, _)| position + 1)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -89,4 +91,20 @@ mod test {
         let mut lines = read_from_string(&lines);
         assert_eq!(find_start_of_message(&mut lines), Some(26));
     }
+
+    #[test]
+    fn test_find_marker_bounds() {
+        assert_eq!(find_marker(b"abcd", 4), Some(4));
+        assert_eq!(find_marker(b"aabcd", 4), Some(5));
+        assert_eq!(find_marker(b"abc", 4), None);
+        assert_eq!(find_marker(b"", 14), None);
+        assert_eq!(find_marker(b"aaaa", 1), Some(1));
+        assert_eq!(find_marker(b"abcdefghijklmnopqrstuvwxyz", 26), Some(26));
+        assert_eq!(find_marker(b"abcdefghijklmnopqrstuvwxyz", 27), None);
+        assert_eq!(find_marker(b"abc", 0), None);
+
+        let lines = r#"abc"#.to_string();
+        let mut lines = read_from_string(&lines);
+        assert_eq!(find_start_of_message(&mut lines), None);
+    }
 }
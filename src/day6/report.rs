@@ -0,0 +1,250 @@
+use super::{find_marker, MarkerDetector, MarkerKind};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    PerLine,
+    SingleStream,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatastreamReport {
+    pub line: usize,
+    pub length: usize,
+    pub start_of_packet: Option<usize>,
+    pub start_of_message: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkerSummary {
+    pub found: usize,
+    pub earliest: Option<usize>,
+    pub latest: Option<usize>,
+    pub mean: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkerStatistics {
+    pub datastreams: usize,
+    pub bytes: usize,
+    pub packets: MarkerSummary,
+    pub messages: MarkerSummary,
+}
+
+fn format_marker(marker: Option<usize>) -> String {
+    match marker {
+        Some(position) => position.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+impl fmt::Display for DatastreamReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {} ({} bytes) : start of packet {}, start of message {}",
+            self.line,
+            self.length,
+            format_marker(self.start_of_packet),
+            format_marker(self.start_of_message)
+        )
+    }
+}
+
+impl MarkerSummary {
+    fn from_markers(markers: &[usize]) -> Self {
+        MarkerSummary {
+            found: markers.len(),
+            earliest: markers.iter().min().copied(),
+            latest: markers.iter().max().copied(),
+            mean: if markers.is_empty() {
+                None
+            } else {
+                Some(markers.iter().sum::<usize>() as f64 / markers.len() as f64)
+            },
+        }
+    }
+}
+
+impl fmt::Display for MarkerSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "found {}", self.found)?;
+        if let (Some(earliest), Some(latest), Some(mean)) = (self.earliest, self.latest, self.mean)
+        {
+            write!(
+                f,
+                ", earliest {}, latest {}, mean {:.1}",
+                earliest, latest, mean
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl MarkerStatistics {
+    pub fn from_reports(reports: &[DatastreamReport]) -> Self {
+        let packets: Vec<usize> = reports
+            .iter()
+            .filter_map(|report| report.start_of_packet)
+            .collect();
+        let messages: Vec<usize> = reports
+            .iter()
+            .filter_map(|report| report.start_of_message)
+            .collect();
+        MarkerStatistics {
+            datastreams: reports.len(),
+            bytes: reports.iter().map(|report| report.length).sum(),
+            packets: MarkerSummary::from_markers(&packets),
+            messages: MarkerSummary::from_markers(&messages),
+        }
+    }
+}
+
+impl fmt::Display for MarkerStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} datastreams, {} bytes\nStart of packet : {}\nStart of message : {}",
+            self.datastreams, self.bytes, self.packets, self.messages
+        )
+    }
+}
+
+// Blank and unreadable lines are skipped but still count for the line numbers
+pub fn report_markers(
+    lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
+    input_mode: InputMode,
+) -> Vec<DatastreamReport> {
+    let lines = lines
+        .enumerate()
+        .filter_map(|(index, line)| Some((index, line.ok()?)))
+        .filter(|(_, line)| !line.is_empty());
+    match input_mode {
+        InputMode::PerLine => lines
+            .map(|(index, line)| DatastreamReport {
+                line: index + 1,
+                length: line.len(),
+                start_of_packet: find_marker(line.as_bytes(), 4),
+                start_of_message: find_marker(line.as_bytes(), 14),
+            })
+            .collect(),
+        InputMode::SingleStream => {
+            let mut detector = MarkerDetector::new();
+            let mut report: Option<DatastreamReport> = None;
+            for (index, line) in lines {
+                let report = report.get_or_insert(DatastreamReport {
+                    line: index + 1,
+                    length: 0,
+                    start_of_packet: None,
+                    start_of_message: None,
+                });
+                report.length += line.len();
+                for event in detector.push(line.as_bytes()) {
+                    let offset = Some(usize::try_from(event.offset).unwrap());
+                    match event.kind {
+                        MarkerKind::StartOfPacket => {
+                            report.start_of_packet = report.start_of_packet.or(offset)
+                        }
+                        MarkerKind::StartOfMessage => {
+                            report.start_of_message = report.start_of_message.or(offset)
+                        }
+                    }
+                }
+            }
+            report.into_iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    const EXAMPLES: &str = r#"mjqjpqmgbljsphdztnvjfqwrcgsmlb
+bvwbjplbgvbhsrlpgdmjqwftvncz
+
+nppdvjthqldpwncqszvftbrmjlhg
+nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg
+zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"#;
+
+    #[test]
+    fn test_report_per_line() {
+        let examples = EXAMPLES.to_string();
+        let mut lines = read_from_string(&examples);
+        let reports = report_markers(&mut lines, InputMode::PerLine);
+        let summary: Vec<(usize, Option<usize>, Option<usize>)> = reports
+            .iter()
+            .map(|report| (report.line, report.start_of_packet, report.start_of_message))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, Some(7), Some(19)),
+                (2, Some(5), Some(23)),
+                (4, Some(6), Some(23)),
+                (5, Some(10), Some(29)),
+                (6, Some(11), Some(26)),
+            ]
+        );
+        assert_eq!(
+            reports[0].to_string(),
+            "Line 1 (30 bytes) : start of packet 7, start of message 19"
+        );
+
+        let statistics = MarkerStatistics::from_reports(&reports);
+        assert_eq!(statistics.datastreams, 5);
+        assert_eq!(statistics.bytes, 151);
+        assert_eq!(statistics.packets.earliest, Some(5));
+        assert_eq!(statistics.messages.latest, Some(29));
+        assert_eq!(
+            statistics.to_string(),
+            r#"5 datastreams, 151 bytes
+Start of packet : found 5, earliest 5, latest 11, mean 7.8
+Start of message : found 5, earliest 19, latest 29, mean 24.0"#
+        );
+    }
+
+    #[test]
+    fn test_report_single_stream() {
+        let stream = "mjqj\npqmgb\n\nljsphdztnvjfqwrcgsmlb".to_string();
+        let mut lines = read_from_string(&stream);
+        let reports = report_markers(&mut lines, InputMode::SingleStream);
+        assert_eq!(
+            reports,
+            vec![DatastreamReport {
+                line: 1,
+                length: 30,
+                start_of_packet: Some(7),
+                start_of_message: Some(19),
+            }]
+        );
+
+        let mut lines = read_from_string(&stream);
+        let reports = report_markers(&mut lines, InputMode::PerLine);
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].start_of_packet, None);
+
+        let mut lines = vec![
+            Ok("abcd".to_string()),
+            Err(io::Error::new(io::ErrorKind::InvalidData, "bad line")),
+            Ok("efgh".to_string()),
+        ]
+        .into_iter();
+        let reports = report_markers(&mut lines, InputMode::PerLine);
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| report.line)
+                .collect::<Vec<usize>>(),
+            vec![1, 3]
+        );
+
+        let statistics = MarkerStatistics::from_reports(&[]);
+        assert_eq!(
+            statistics.to_string().lines().nth(1),
+            Some("Start of packet : found 0")
+        );
+    }
+}
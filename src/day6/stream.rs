@@ -0,0 +1,230 @@
+use std::io;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    StartOfPacket,
+    StartOfMessage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkerEvent {
+    pub kind: MarkerKind,
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct MarkerWindow {
+    sequence_size: u64,
+    last_seen: [Option<u64>; 256],
+    window_start: u64,
+}
+
+impl MarkerWindow {
+    pub(super) fn new(sequence_size: usize) -> Self {
+        MarkerWindow {
+            sequence_size: u64::try_from(sequence_size).unwrap(),
+            last_seen: [None; 256],
+            window_start: 0,
+        }
+    }
+
+    // Returns true when the byte at position completes a marker, the next one starts after it
+    pub(super) fn push(&mut self, position: u64, byte: u8) -> bool {
+        if self.sequence_size == 0 {
+            return false;
+        }
+        if let Some(previous) = self.last_seen[usize::from(byte)] {
+            if previous >= self.window_start {
+                self.window_start = previous + 1;
+            }
+        }
+        self.last_seen[usize::from(byte)] = Some(position);
+        if position + 1 - self.window_start == self.sequence_size {
+            self.window_start = position + 1;
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    offset: u64,
+    packet: MarkerWindow,
+    message: MarkerWindow,
+}
+
+impl Default for MarkerDetector {
+    fn default() -> Self {
+        MarkerDetector::new()
+    }
+}
+
+impl MarkerDetector {
+    pub fn new() -> Self {
+        MarkerDetector::with_sizes(4, 14)
+    }
+
+    pub fn with_sizes(packet_size: usize, message_size: usize) -> Self {
+        MarkerDetector {
+            offset: 0,
+            packet: MarkerWindow::new(packet_size),
+            message: MarkerWindow::new(message_size),
+        }
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<MarkerEvent> {
+        let mut events = vec![];
+        for byte in bytes {
+            let position = self.offset;
+            self.offset += 1;
+            if self.packet.push(position, *byte) {
+                events.push(MarkerEvent {
+                    kind: MarkerKind::StartOfPacket,
+                    offset: self.offset,
+                });
+            }
+            if self.message.push(position, *byte) {
+                events.push(MarkerEvent {
+                    kind: MarkerKind::StartOfMessage,
+                    offset: self.offset,
+                });
+            }
+        }
+        events
+    }
+
+    pub fn events<R: io::Read>(self, reader: R) -> MarkerEvents<R> {
+        MarkerEvents {
+            detector: self,
+            reader,
+            buffer: vec![0; READ_BUFFER_SIZE],
+            pending: vec![],
+            done: false,
+        }
+    }
+
+    pub fn read_all<R: io::Read>(&mut self, mut reader: R) -> io::Result<Vec<MarkerEvent>> {
+        let mut buffer = vec![0; READ_BUFFER_SIZE];
+        let mut events = vec![];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(events),
+                Ok(read) => events.extend(self.push(&buffer[..read])),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+pub struct MarkerEvents<R> {
+    detector: MarkerDetector,
+    reader: R,
+    buffer: Vec<u8>,
+    pending: Vec<MarkerEvent>,
+    done: bool,
+}
+
+impl<R: io::Read> Iterator for MarkerEvents<R> {
+    type Item = io::Result<MarkerEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.done {
+                return None;
+            }
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(read) => {
+                    self.pending = self.detector.push(&self.buffer[..read]);
+                    self.pending.reverse();
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+        self.pending.pop().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn first(events: &[MarkerEvent], kind: MarkerKind) -> Option<u64> {
+        events
+            .iter()
+            .find(|event| event.kind == kind)
+            .map(|event| event.offset)
+    }
+
+    #[test]
+    fn test_push_in_chunks() {
+        let examples: [(&[u8], u64, u64); 5] = [
+            (b"mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            (b"bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            (b"nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            (b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            (b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (datastream, packet, message) in examples {
+            for chunk_size in [1, 3, 7, datastream.len()] {
+                let mut detector = MarkerDetector::new();
+                let events: Vec<MarkerEvent> = datastream
+                    .chunks(chunk_size)
+                    .flat_map(|chunk| detector.push(chunk))
+                    .collect();
+                assert_eq!(first(&events, MarkerKind::StartOfPacket), Some(packet));
+                assert_eq!(first(&events, MarkerKind::StartOfMessage), Some(message));
+                assert_eq!(detector.offset(), datastream.len() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_marker() {
+        let mut detector = MarkerDetector::with_sizes(4, 14);
+        let events = detector.push(b"abcdabcdaaaaefgh");
+        assert_eq!(
+            events,
+            vec![
+                MarkerEvent {
+                    kind: MarkerKind::StartOfPacket,
+                    offset: 4
+                },
+                MarkerEvent {
+                    kind: MarkerKind::StartOfPacket,
+                    offset: 8
+                },
+                MarkerEvent {
+                    kind: MarkerKind::StartOfPacket,
+                    offset: 15
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let datastream: &[u8] = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let events = MarkerDetector::new().read_all(datastream).unwrap();
+        assert_eq!(first(&events, MarkerKind::StartOfPacket), Some(7));
+
+        let events: Vec<MarkerEvent> = MarkerDetector::new()
+            .events(datastream)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(first(&events, MarkerKind::StartOfMessage), Some(19));
+        assert_eq!(events[0].kind, MarkerKind::StartOfPacket);
+    }
+}
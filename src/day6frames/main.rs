@@ -0,0 +1,61 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
+const USAGE: &str = "Usage: day6frames [--packet-size N] [--message-size N] [FILE]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn parse_size(value: Option<String>) -> usize {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| usage())
+}
+
+fn main() {
+    use adventofcode::day6::*;
+    let mut packet_size = 4;
+    let mut message_size = 14;
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--packet-size" => packet_size = parse_size(args.next()),
+            "--message-size" => message_size = parse_size(args.next()),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let mut input: Box<dyn Read> = match &path {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(file),
+            Err(error) => {
+                eprintln!("{}: {}", path, error);
+                process::exit(1);
+            }
+        },
+        None => Box::new(io::stdin()),
+    };
+
+    let mut framer = Framer::with_sizes(packet_size, message_size);
+    let mut frames = vec![];
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        match input.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => frames.extend(framer.push(&buffer[..read])),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        }
+    }
+    frames.extend(framer.finish());
+    println!("{}", format_frames(&frames));
+}
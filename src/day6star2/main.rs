@@ -1,11 +1,27 @@
+use std::env;
 use std::io;
 
 fn main() {
     use adventofcode::day6::*;
     let mut lines = io::stdin().lines();
+    let input_mode = if env::args().any(|arg| arg == "--single-stream") {
+        InputMode::SingleStream
+    } else {
+        InputMode::PerLine
+    };
 
-    println!(
-        "Start of packet : {}",
-        find_start_of_message(&mut lines).unwrap()
-    );
+    let reports = report_markers(&mut lines, input_mode);
+    for report in &reports {
+        match report.start_of_message {
+            Some(position) => println!("Start of message (line {}) : {}", report.line, position),
+            None => println!("Start of message (line {}) : none", report.line),
+        }
+    }
+    if reports.len() > 1 {
+        let statistics = MarkerStatistics::from_reports(&reports);
+        println!(
+            "{} datastreams, start of message {}",
+            statistics.datastreams, statistics.messages
+        );
+    }
 }
@@ -0,0 +1,322 @@
+use super::{FileSystem, NodeId, WalkOrder};
+use std::collections::HashMap;
+use std::fmt;
+
+const MAX_CLEANUP_STEPS: usize = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanupPolicy {
+    pub capacity: usize,
+    pub required_free: usize,
+    pub alternatives: usize,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        CleanupPolicy {
+            capacity: 70000000,
+            required_free: 30000000,
+            alternatives: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupStrategy {
+    SingleDirectory,
+    DirectorySet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupCandidate {
+    pub paths: Vec<String>,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupPlan {
+    pub policy: CleanupPolicy,
+    pub strategy: CleanupStrategy,
+    pub used: usize,
+    pub to_free: usize,
+    pub choice: Option<CleanupCandidate>,
+    pub alternatives: Vec<CleanupCandidate>,
+    pub exhaustive: bool,
+}
+
+impl fmt::Display for CleanupCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.paths.join(", "), self.size)
+    }
+}
+
+impl fmt::Display for CleanupPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Capacity {}, used {}, free {}",
+            self.policy.capacity,
+            self.used,
+            self.policy.capacity.saturating_sub(self.used)
+        )?;
+        write!(
+            f,
+            "Required free {}, {} to free",
+            self.policy.required_free, self.to_free
+        )?;
+        match &self.choice {
+            Some(choice) if choice.paths.is_empty() => write!(f, "\nNothing to delete")?,
+            Some(choice) => write!(f, "\nDelete {}", choice)?,
+            None => write!(f, "\nNo deletion frees enough space")?,
+        }
+        if !self.alternatives.is_empty() {
+            write!(f, "\nAlternatives:")?;
+            for alternative in &self.alternatives {
+                write!(f, "\n  {}", alternative)?;
+            }
+        }
+        if !self.exhaustive {
+            write!(f, "\nSearch stopped early, a smaller set may exist")?;
+        }
+        Ok(())
+    }
+}
+
+struct SetSearch {
+    candidates: Vec<(NodeId, usize)>,
+    ancestors: HashMap<NodeId, Vec<NodeId>>,
+    to_free: usize,
+    keep: usize,
+    best: Vec<(usize, Vec<NodeId>)>,
+    steps: usize,
+}
+
+impl SetSearch {
+    fn bound(&self) -> usize {
+        if self.best.len() < self.keep {
+            usize::MAX
+        } else {
+            self.best.last().map_or(usize::MAX, |(size, _)| *size)
+        }
+    }
+
+    fn record(&mut self, size: usize, chosen: &[NodeId]) {
+        let mut chosen = chosen.to_vec();
+        chosen.sort();
+        if self.best.iter().any(|(_, set)| *set == chosen) {
+            return;
+        }
+        let position = self
+            .best
+            .partition_point(|(best_size, _)| *best_size <= size);
+        self.best.insert(position, (size, chosen));
+        self.best.truncate(self.keep);
+    }
+
+    fn conflicts(&self, candidate: NodeId, chosen: &[NodeId]) -> bool {
+        chosen.iter().any(|other| {
+            self.ancestors[&candidate].contains(other) || self.ancestors[other].contains(&candidate)
+        })
+    }
+
+    // Candidates come by decreasing size, remaining[i] bounds what candidates from i on can add
+    fn search(&mut self, start: usize, size: usize, chosen: &mut Vec<NodeId>, remaining: &[usize]) {
+        self.steps += 1;
+        if size >= self.to_free {
+            self.record(size, chosen);
+            return;
+        }
+        if self.steps > MAX_CLEANUP_STEPS
+            || self.bound() <= self.to_free
+            || start >= self.candidates.len()
+            || size + remaining[start] < self.to_free
+        {
+            return;
+        }
+        for index in start..self.candidates.len() {
+            let (candidate, candidate_size) = self.candidates[index];
+            if size + candidate_size >= self.bound() || self.conflicts(candidate, chosen) {
+                continue;
+            }
+            if size + remaining[index] < self.to_free {
+                break;
+            }
+            chosen.push(candidate);
+            self.search(index + 1, size + candidate_size, chosen, remaining);
+            chosen.pop();
+        }
+    }
+}
+
+impl FileSystem {
+    fn cleanup_candidate(&self, ids: &[NodeId]) -> CleanupCandidate {
+        CleanupCandidate {
+            paths: ids.iter().map(|id| self.path_of(*id)).collect(),
+            size: ids.iter().map(|id| self.node(*id).size()).sum(),
+        }
+    }
+
+    fn ancestors_of(&self, id: NodeId) -> Vec<NodeId> {
+        let mut ancestors = vec![];
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            ancestors.push(parent);
+            current = parent;
+        }
+        ancestors
+    }
+
+    pub fn plan_cleanup(&self, policy: &CleanupPolicy, strategy: CleanupStrategy) -> CleanupPlan {
+        let used = self.node(self.root()).size();
+        let to_free = policy
+            .required_free
+            .saturating_sub(policy.capacity.saturating_sub(used));
+        let mut plan = CleanupPlan {
+            policy: *policy,
+            strategy,
+            used,
+            to_free,
+            choice: None,
+            alternatives: vec![],
+            exhaustive: true,
+        };
+        if to_free == 0 {
+            plan.choice = Some(self.cleanup_candidate(&[]));
+            return plan;
+        }
+
+        let mut directories: Vec<(NodeId, usize)> = self
+            .walk(WalkOrder::DepthFirst)
+            .filter(|(_, id)| self.node(*id).is_dir() && self.node(*id).size() > 0)
+            .map(|(_, id)| (id, self.node(id).size()))
+            .collect();
+        let mut found: Vec<Vec<NodeId>> = match strategy {
+            CleanupStrategy::SingleDirectory => {
+                directories.retain(|(_, size)| *size >= to_free);
+                directories.sort_by_key(|(_, size)| *size);
+                directories
+                    .iter()
+                    .take(policy.alternatives + 1)
+                    .map(|(id, _)| vec![*id])
+                    .collect()
+            }
+            CleanupStrategy::DirectorySet => {
+                // A directory holding all of its parent's content frees the same space as the parent
+                directories.retain(|(id, size)| {
+                    self.parent(*id)
+                        .is_none_or(|parent| self.node(parent).size() != *size)
+                });
+                directories.sort_by(|(_, size_a), (_, size_b)| size_b.cmp(size_a));
+                let mut remaining = vec![0; directories.len() + 1];
+                for index in (0..directories.len()).rev() {
+                    remaining[index] = remaining[index + 1] + directories[index].1;
+                }
+                let mut search = SetSearch {
+                    ancestors: directories
+                        .iter()
+                        .map(|(id, _)| (*id, self.ancestors_of(*id)))
+                        .collect(),
+                    candidates: directories,
+                    to_free,
+                    keep: policy.alternatives + 1,
+                    best: vec![],
+                    steps: 0,
+                };
+                search.search(0, 0, &mut vec![], &remaining);
+                plan.exhaustive = search.steps <= MAX_CLEANUP_STEPS;
+                search
+                    .best
+                    .into_iter()
+                    .map(|(_, mut set)| {
+                        set.sort_by_key(|id| self.path_of(*id));
+                        set
+                    })
+                    .collect()
+            }
+        };
+        if !found.is_empty() {
+            plan.choice = Some(self.cleanup_candidate(&found.remove(0)));
+        }
+        plan.alternatives = found
+            .iter()
+            .map(|ids| self.cleanup_candidate(ids))
+            .collect();
+        plan
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> FileSystem {
+        let mut fs = FileSystem::make_empty_fs();
+        let a = fs.append_dir(fs.root(), "a").unwrap();
+        let b = fs.append_dir(a, "b").unwrap();
+        fs.append_file(b, "x", 40);
+        fs.append_file(a, "y", 35);
+        let c = fs.append_dir(fs.root(), "c").unwrap();
+        fs.append_file(c, "z", 30);
+        let d = fs.append_dir(fs.root(), "d").unwrap();
+        fs.append_file(d, "w", 100);
+        fs.append_file(fs.root(), "v", 5);
+        fs
+    }
+
+    fn policy(capacity: usize, required_free: usize) -> CleanupPolicy {
+        CleanupPolicy {
+            capacity,
+            required_free,
+            alternatives: 2,
+        }
+    }
+
+    #[test]
+    fn test_single_directory() {
+        let fs = sample();
+        let plan = fs.plan_cleanup(&policy(250, 70), CleanupStrategy::SingleDirectory);
+        assert_eq!(plan.used, 210);
+        assert_eq!(plan.to_free, 30);
+        assert_eq!(plan.choice.unwrap().paths, vec!["/c"]);
+        assert_eq!(
+            plan.alternatives
+                .iter()
+                .map(|alternative| alternative.to_string())
+                .collect::<Vec<String>>(),
+            vec!["/a/b (40)", "/a (75)"]
+        );
+
+        let plan = fs.plan_cleanup(&policy(250, 30), CleanupStrategy::SingleDirectory);
+        assert_eq!(plan.to_free, 0);
+        assert_eq!(plan.choice.unwrap().size, 0);
+
+        let plan = fs.plan_cleanup(&policy(100, 300), CleanupStrategy::SingleDirectory);
+        assert_eq!(plan.choice, None);
+    }
+
+    #[test]
+    fn test_directory_set() {
+        let fs = sample();
+        let plan = fs.plan_cleanup(&policy(250, 110), CleanupStrategy::DirectorySet);
+        assert_eq!(plan.to_free, 70);
+        let choice = plan.choice.clone().unwrap();
+        assert_eq!(choice.paths, vec!["/a/b", "/c"]);
+        assert_eq!(choice.size, 70);
+        assert!(plan.exhaustive);
+        assert_eq!(
+            plan.to_string(),
+            r#"Capacity 250, used 210, free 40
+Required free 110, 70 to free
+Delete /a/b, /c (70)
+Alternatives:
+  /a (75)
+  /d (100)"#
+        );
+
+        let single = fs.plan_cleanup(&policy(250, 110), CleanupStrategy::SingleDirectory);
+        assert_eq!(single.choice.unwrap().paths, vec!["/a"]);
+
+        let plan = fs.plan_cleanup(&policy(210, 210), CleanupStrategy::DirectorySet);
+        assert_eq!(plan.choice.unwrap().paths, vec!["/"]);
+    }
+}
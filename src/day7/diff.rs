@@ -0,0 +1,330 @@
+use super::query::join_path;
+use super::{FileSystem, NodeId};
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Resized,
+    // A directory whose content changed without changing its size
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub path: String,
+    pub name: String,
+    pub depth: usize,
+    pub is_dir: bool,
+    pub change: ChangeKind,
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+}
+
+// Entries come depth first, parents before their content; unchanged nodes are left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystemDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl ChangeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Resized => "resized",
+            ChangeKind::Modified => "modified",
+        }
+    }
+
+    fn marker(&self) -> char {
+        match self {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Resized => '~',
+            ChangeKind::Modified => ' ',
+        }
+    }
+}
+
+impl DiffEntry {
+    // Rolled up over the whole content for directories
+    pub fn delta(&self) -> i64 {
+        self.after.unwrap_or(0) as i64 - self.before.unwrap_or(0) as i64
+    }
+
+    pub fn to_json_value(&self) -> Value {
+        json!({
+            "path": self.path,
+            "type": if self.is_dir { "dir" } else { "file" },
+            "change": self.change.as_str(),
+            "before": self.before,
+            "after": self.after,
+            "delta": self.delta(),
+        })
+    }
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}- {} ({}, ",
+            self.change.marker(),
+            "  ".repeat(self.depth),
+            self.name,
+            if self.is_dir { "dir" } else { "file" }
+        )?;
+        match (self.before, self.after) {
+            (Some(before), Some(after)) if before != after => {
+                write!(f, "size={} -> {}, {:+})", before, after, self.delta())
+            }
+            (Some(size), _) | (None, Some(size)) => write!(f, "size={})", size),
+            (None, None) => write!(f, "size=0)"),
+        }
+    }
+}
+
+impl FileSystemDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn count(&self, change: ChangeKind) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.change == change)
+            .count()
+    }
+
+    pub fn directory_deltas(&self) -> Vec<(String, i64)> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_dir && entry.delta() != 0)
+            .map(|entry| (entry.path.clone(), entry.delta()))
+            .collect()
+    }
+
+    pub fn to_json_value(&self) -> Value {
+        let entries: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| entry.to_json_value())
+            .collect();
+        json!({
+            "added": self.count(ChangeKind::Added),
+            "removed": self.count(ChangeKind::Removed),
+            "resized": self.count(ChangeKind::Resized),
+            "delta": self.entries.first().map_or(0, |root| root.delta()),
+            "entries": entries,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json_value()).unwrap()
+    }
+}
+
+// Unified tree diff: '+' added, '-' removed, '~' resized, ' ' for directories kept as context
+impl fmt::Display for FileSystemDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines: Vec<String> = self.entries.iter().map(|entry| entry.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+struct Differ<'a> {
+    before: &'a FileSystem,
+    after: &'a FileSystem,
+    entries: Vec<DiffEntry>,
+}
+
+impl Differ<'_> {
+    fn whole(
+        &mut self,
+        fs: &FileSystem,
+        id: NodeId,
+        path: String,
+        depth: usize,
+        change: ChangeKind,
+    ) {
+        let node = fs.node(id);
+        let (before, after) = match change {
+            ChangeKind::Removed => (Some(node.size()), None),
+            _ => (None, Some(node.size())),
+        };
+        self.entries.push(DiffEntry {
+            name: node.name().to_string(),
+            path: path.clone(),
+            depth,
+            is_dir: node.is_dir(),
+            change,
+            before,
+            after,
+        });
+        for child in node.children() {
+            let child_path = join_path(&path, fs.node(*child).name());
+            self.whole(fs, *child, child_path, depth + 1, change);
+        }
+    }
+
+    fn compare(&mut self, before_id: NodeId, after_id: NodeId, path: String, depth: usize) {
+        let before = self.before.node(before_id);
+        let after = self.after.node(after_id);
+        if before.is_dir() != after.is_dir() {
+            self.whole(
+                self.before,
+                before_id,
+                path.clone(),
+                depth,
+                ChangeKind::Removed,
+            );
+            self.whole(self.after, after_id, path, depth, ChangeKind::Added);
+            return;
+        }
+        let position = self.entries.len();
+        self.entries.push(DiffEntry {
+            name: after.name().to_string(),
+            path: path.clone(),
+            depth,
+            is_dir: after.is_dir(),
+            change: if before.size() == after.size() {
+                ChangeKind::Modified
+            } else {
+                ChangeKind::Resized
+            },
+            before: Some(before.size()),
+            after: Some(after.size()),
+        });
+        for child in before.children() {
+            let name = self.before.node(*child).name();
+            let child_path = join_path(&path, name);
+            match self.after.child(after_id, name) {
+                Some(other) => self.compare(*child, other, child_path, depth + 1),
+                None => self.whole(
+                    self.before,
+                    *child,
+                    child_path,
+                    depth + 1,
+                    ChangeKind::Removed,
+                ),
+            }
+        }
+        for child in after.children() {
+            let name = self.after.node(*child).name();
+            if self.before.child(before_id, name).is_none() {
+                let child_path = join_path(&path, name);
+                self.whole(self.after, *child, child_path, depth + 1, ChangeKind::Added);
+            }
+        }
+        if self.entries.len() == position + 1 && self.entries[position].delta() == 0 {
+            self.entries.truncate(position);
+        }
+    }
+}
+
+impl FileSystem {
+    // Children are matched by name, a file replaced by a directory shows as removed then added
+    pub fn diff(&self, other: &FileSystem) -> FileSystemDiff {
+        let mut differ = Differ {
+            before: self,
+            after: other,
+            entries: vec![],
+        };
+        differ.compare(self.root(), other.root(), "/".to_string(), 0);
+        FileSystemDiff {
+            entries: differ.entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    fn parse(transcript: &str) -> FileSystem {
+        let transcript = transcript.to_string();
+        FileSystem::make_from_listing(&mut read_from_string(&transcript))
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = parse(
+            r#"$ cd /
+$ ls
+dir a
+100 b.txt
+dir d
+$ cd a
+$ ls
+20 f
+30 g
+$ cd ..
+$ cd d
+$ ls
+dir old
+5 keep
+$ cd old
+$ ls
+7 x"#,
+        );
+        let after = parse(
+            r#"$ cd /
+$ ls
+dir a
+100 b.txt
+dir d
+10 new
+$ cd a
+$ ls
+25 f
+25 h
+$ cd ..
+$ cd d
+$ ls
+5 keep
+7 old"#,
+        );
+        assert!(before.diff(&before).is_empty());
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.to_string(),
+            r#"~- / (dir, size=162 -> 172, +10)
+   - a (dir, size=50)
+~    - f (file, size=20 -> 25, +5)
+-    - g (file, size=30)
++    - h (file, size=25)
+   - d (dir, size=12)
+-    - old (dir, size=7)
+-      - x (file, size=7)
++    - old (file, size=7)
++  - new (file, size=10)"#
+        );
+        assert_eq!(diff.count(ChangeKind::Added), 3);
+        assert_eq!(diff.count(ChangeKind::Removed), 3);
+        assert_eq!(diff.count(ChangeKind::Resized), 2);
+        assert_eq!(
+            diff.directory_deltas(),
+            vec![("/".to_string(), 10), ("/d/old".to_string(), -7)]
+        );
+
+        let json = diff.to_json_value();
+        assert_eq!(json["delta"], 10);
+        assert_eq!(
+            json["entries"][2],
+            json!({
+                "path": "/a/f",
+                "type": "file",
+                "change": "resized",
+                "before": 20,
+                "after": 25,
+                "delta": 5,
+            })
+        );
+        assert_eq!(after.diff(&before).to_json_value()["delta"], -10);
+    }
+}
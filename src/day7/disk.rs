@@ -0,0 +1,358 @@
+use super::query::matches_name;
+use super::transcript::is_valid_name;
+use super::{FileSystem, NodeId};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskOptions {
+    pub follow_symlinks: bool,
+    pub max_depth: Option<usize>,
+    pub ignore: Vec<String>,
+}
+
+impl DiskOptions {
+    fn is_ignored(&self, name: &str) -> bool {
+        let name: Vec<char> = name.chars().collect();
+        self.ignore.iter().any(|pattern| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            matches_name(&pattern, &name)
+        })
+    }
+}
+
+// An entry that could not be read or added, the walk goes on without it
+#[derive(Debug)]
+pub struct DiskIssue {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl fmt::Display for DiskIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl FileSystem {
+    // Unreadable entries are left out, use from_disk_with to know which
+    pub fn from_disk<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_disk_with(path, &DiskOptions::default()).map(|(fs, _)| fs)
+    }
+
+    // Directories below max_depth are kept but left empty, symlinks are skipped unless followed.
+    // Only an unreadable root fails, other read errors are returned as issues
+    pub fn from_disk_with<P: AsRef<Path>>(
+        path: P,
+        options: &DiskOptions,
+    ) -> io::Result<(Self, Vec<DiskIssue>)> {
+        let mut fs = Self::make_empty_fs();
+        let root = fs.root();
+        let mut issues = vec![];
+        fs.read_directory(
+            path.as_ref(),
+            root,
+            0,
+            options,
+            &mut HashSet::new(),
+            &mut issues,
+        )?;
+        Ok((fs, issues))
+    }
+
+    fn read_directory(
+        &mut self,
+        path: &Path,
+        directory: NodeId,
+        depth: usize,
+        options: &DiskOptions,
+        ancestors: &mut HashSet<PathBuf>,
+        issues: &mut Vec<DiskIssue>,
+    ) -> io::Result<()> {
+        // A followed symlink pointing back to an ancestor would loop forever
+        let canonical = fs::canonicalize(path)?;
+        if !ancestors.insert(canonical.clone()) {
+            return Ok(());
+        }
+        let result = self.read_entries(path, directory, depth, options, ancestors, issues);
+        ancestors.remove(&canonical);
+        result
+    }
+
+    fn read_entries(
+        &mut self,
+        path: &Path,
+        directory: NodeId,
+        depth: usize,
+        options: &DiskOptions,
+        ancestors: &mut HashSet<PathBuf>,
+        issues: &mut Vec<DiskIssue>,
+    ) -> io::Result<()> {
+        let mut entries = vec![];
+        for entry in fs::read_dir(path)? {
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(error) => issues.push(DiskIssue {
+                    path: path.to_path_buf(),
+                    error,
+                }),
+            }
+        }
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if options.is_ignored(&name) {
+                continue;
+            }
+            if !is_valid_name(&name) {
+                issues.push(DiskIssue {
+                    path: entry.path(),
+                    error: io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{:?} cannot be written to a transcript", name),
+                    ),
+                });
+                continue;
+            }
+            let metadata = match entry.file_type() {
+                Ok(file_type) if file_type.is_symlink() => {
+                    if !options.follow_symlinks {
+                        continue;
+                    }
+                    match fs::metadata(entry.path()) {
+                        // Dangling link
+                        Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                        metadata => metadata,
+                    }
+                }
+                Ok(_) => entry.metadata(),
+                Err(error) => Err(error),
+            };
+            let metadata = match metadata {
+                Ok(metadata) => metadata,
+                Err(error) => {
+                    issues.push(DiskIssue {
+                        path: entry.path(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            if metadata.is_dir() {
+                let Some(child) = self.append_dir(directory, &name) else {
+                    issues.push(Self::name_collision(&entry.path(), &name));
+                    continue;
+                };
+                if options.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    // The directory stays, empty, when its content cannot be read
+                    if let Err(error) = self.read_directory(
+                        &entry.path(),
+                        child,
+                        depth + 1,
+                        options,
+                        ancestors,
+                        issues,
+                    ) {
+                        issues.push(DiskIssue {
+                            path: entry.path(),
+                            error,
+                        });
+                    }
+                }
+            } else if self
+                .append_file(directory, &name, usize::try_from(metadata.len()).unwrap())
+                .is_none()
+            {
+                issues.push(Self::name_collision(&entry.path(), &name));
+            }
+        }
+        Ok(())
+    }
+
+    // Names that are not UTF-8 are read lossily, two of them can end up the same
+    fn name_collision(path: &Path, name: &str) -> DiskIssue {
+        DiskIssue {
+            path: path.to_path_buf(),
+            error: io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("another entry is already named {}", name),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("day7-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn file(&self, path: &str, size: usize) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, vec![b'x'; size]).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_from_disk() {
+        let temp = TempDir::new("from-disk");
+        temp.file("b.txt", 120000);
+        temp.file("a/f", 29116);
+        temp.file("a/e/i", 584);
+        temp.file("d/j", 4000);
+        temp.file("d/d.log", 300);
+        fs::create_dir(temp.0.join("empty")).unwrap();
+
+        let fs = FileSystem::from_disk(&temp.0).unwrap();
+        assert_eq!(fs.size_of("/"), Some(154000));
+        assert_eq!(fs.size_of("/a"), Some(29700));
+        assert_eq!(fs.size_of("/a/e/i"), Some(584));
+        assert_eq!(fs.size_of("/empty"), Some(0));
+        assert_eq!(fs.total_size_directories(), 29700 + 584 + 4300);
+        assert_eq!(fs.max_directory_size(), 154000);
+
+        let options = DiskOptions {
+            ignore: vec!["*.log".to_string(), "e".to_string()],
+            max_depth: Some(0),
+            ..DiskOptions::default()
+        };
+        let (fs, _) = FileSystem::from_disk_with(&temp.0, &options).unwrap();
+        assert_eq!(fs.size_of("/"), Some(120000));
+        assert_eq!(fs.size_of("/d"), Some(0));
+
+        let options = DiskOptions {
+            ignore: vec!["*.log".to_string(), "e".to_string()],
+            ..DiskOptions::default()
+        };
+        let (fs, _) = FileSystem::from_disk_with(&temp.0, &options).unwrap();
+        assert_eq!(fs.get("/d/d.log"), None);
+        assert_eq!(fs.get("/a/e"), None);
+        assert_eq!(fs.size_of("/"), Some(153116));
+
+        assert!(FileSystem::from_disk(temp.0.join("missing")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_disk_symlinks() {
+        let temp = TempDir::new("symlinks");
+        temp.file("data/f", 10);
+        std::os::unix::fs::symlink(temp.0.join("data"), temp.0.join("link")).unwrap();
+        std::os::unix::fs::symlink(&temp.0, temp.0.join("data/loop")).unwrap();
+
+        let fs = FileSystem::from_disk(&temp.0).unwrap();
+        assert_eq!(fs.get("/link"), None);
+        assert_eq!(fs.size_of("/"), Some(10));
+
+        let options = DiskOptions {
+            follow_symlinks: true,
+            ..DiskOptions::default()
+        };
+        let (fs, _) = FileSystem::from_disk_with(&temp.0, &options).unwrap();
+        assert_eq!(fs.size_of("/"), Some(20));
+        assert_eq!(fs.size_of("/link/f"), Some(10));
+        assert_eq!(fs.size_of("/data/loop"), Some(0));
+
+        // A link to itself cannot be resolved, the rest of the tree is still read
+        std::os::unix::fs::symlink(temp.0.join("self"), temp.0.join("self")).unwrap();
+        let (fs, issues) = FileSystem::from_disk_with(&temp.0, &options).unwrap();
+        assert_eq!(fs.size_of("/"), Some(20));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, temp.0.join("self"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_disk_unreadable() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp = TempDir::new("unreadable");
+        temp.file("open/f", 10);
+        temp.file("locked/g", 20);
+        let locked = temp.0.join("locked");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        let readable = fs::read_dir(&locked).is_ok();
+        let result = FileSystem::from_disk_with(&temp.0, &DiskOptions::default());
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (fs, issues) = result.unwrap();
+        assert_eq!(fs.size_of("/open"), Some(10));
+        // Running as root bypasses permissions and reads the directory anyway
+        if readable {
+            assert_eq!(fs.size_of("/locked"), Some(20));
+            assert!(issues.is_empty());
+        } else {
+            assert_eq!(fs.size_of("/locked"), Some(0));
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].path, locked);
+            assert_eq!(issues[0].error.kind(), io::ErrorKind::PermissionDenied);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_disk_invalid_names() {
+        let temp = TempDir::new("invalid-names");
+        temp.file("ok", 1);
+        temp.file("a\nb", 2);
+        temp.file(" lead", 4);
+        temp.file("dir /f", 8);
+
+        let (fs, mut issues) =
+            FileSystem::from_disk_with(&temp.0, &DiskOptions::default()).unwrap();
+        assert_eq!(fs.size_of("/"), Some(1));
+        issues.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| issue.path.clone())
+                .collect::<Vec<PathBuf>>(),
+            vec![
+                temp.0.join(" lead"),
+                temp.0.join("a\nb"),
+                temp.0.join("dir ")
+            ]
+        );
+        assert!(issues
+            .iter()
+            .all(|issue| issue.error.kind() == io::ErrorKind::InvalidData));
+        let transcript = fs.to_transcript().unwrap();
+        let mut lines = crate::testtools::read_from_string(&transcript);
+        assert_eq!(FileSystem::make_from_listing(&mut lines), fs);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_disk_lossy_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let temp = TempDir::new("lossy-names");
+        let first = temp.0.join(OsStr::from_bytes(b"a\xfe"));
+        let second = temp.0.join(OsStr::from_bytes(b"a\xff"));
+        fs::write(&first, vec![b'x'; 10]).unwrap();
+        fs::write(&second, vec![b'x'; 20]).unwrap();
+
+        let (fs, issues) = FileSystem::from_disk_with(&temp.0, &DiskOptions::default()).unwrap();
+        assert_eq!(fs.size_of("/a\u{fffd}"), Some(10));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, second);
+        assert_eq!(issues[0].error.kind(), io::ErrorKind::AlreadyExists);
+    }
+}
@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptIssueKind {
+    UnknownCommand(String),
+    UnexpectedOutput(String),
+    InvalidName(String),
+    UnknownDirectory(String),
+    NotADirectory(String),
+    ConflictingSize {
+        path: String,
+        previous: usize,
+        listed: usize,
+    },
+    ConflictingType(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptIssue {
+    pub line: usize,
+    pub kind: TranscriptIssueKind,
+}
+
+impl TranscriptIssue {
+    pub fn new(line: usize, kind: TranscriptIssueKind) -> Self {
+        TranscriptIssue { line, kind }
+    }
+
+    // Warnings are recovered from, errors mean part of the transcript was not applied
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self.kind,
+            TranscriptIssueKind::UnexpectedOutput(_) | TranscriptIssueKind::UnknownDirectory(_)
+        )
+    }
+}
+
+impl fmt::Display for TranscriptIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: ",
+            self.line,
+            if self.is_error() { "error" } else { "warning" }
+        )?;
+        match &self.kind {
+            TranscriptIssueKind::UnknownCommand(command) => {
+                write!(f, "unknown command \"{}\"", command)
+            }
+            TranscriptIssueKind::UnexpectedOutput(output) => {
+                write!(f, "unexpected output \"{}\"", output)
+            }
+            TranscriptIssueKind::InvalidName(name) => write!(f, "invalid name \"{}\"", name),
+            TranscriptIssueKind::UnknownDirectory(path) => {
+                write!(f, "directory {} was never listed, created it", path)
+            }
+            TranscriptIssueKind::NotADirectory(path) => write!(f, "{} is not a directory", path),
+            TranscriptIssueKind::ConflictingSize {
+                path,
+                previous,
+                listed,
+            } => write!(
+                f,
+                "{} listed with size {} but was {} before",
+                path, listed, previous
+            ),
+            TranscriptIssueKind::ConflictingType(path) => {
+                write!(f, "{} listed both as a file and a directory", path)
+            }
+        }
+    }
+}
+
+impl Error for TranscriptIssue {}
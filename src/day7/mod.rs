@@ -1,146 +1,92 @@
-use std::cell::RefCell;
-use std::collections::VecDeque;
+mod cleanup;
+mod diff;
+mod disk;
+mod error;
+mod query;
+mod render;
+mod serialize;
+mod shell;
+mod transcript;
+
 use std::io;
-use std::rc::Rc;
 
-#[derive(Debug)]
-pub enum Node {
-    File(String, usize),
-    Dir(String, Rc<RefCell<Vec<Rc<RefCell<Node>>>>>),
+pub use cleanup::{CleanupCandidate, CleanupPlan, CleanupPolicy, CleanupStrategy};
+pub use diff::{ChangeKind, DiffEntry, FileSystemDiff};
+pub use disk::{DiskIssue, DiskOptions};
+pub use error::{TranscriptIssue, TranscriptIssueKind};
+pub use query::{Walk, WalkOrder};
+pub use render::{human_size, RenderOptions};
+pub use serialize::SnapshotError;
+pub use shell::{Shell, ShellError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeKind {
+    File,
+    Dir(Vec<NodeId>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    name: String,
+    parent: Option<NodeId>,
+    size: usize,
+    kind: NodeKind,
 }
 
 impl Node {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Directories cache the total size of their content
     pub fn size(&self) -> usize {
-        match self {
-            Self::File(_, size) => *size,
-            Self::Dir(_, nodes) => nodes
-                .borrow()
-                .iter()
-                .fold(0, |accu, node| accu + node.borrow().size()),
-        }
+        self.size
     }
 
-    pub fn name(&self) -> &str {
-        match self {
-            Self::Dir(ref name, _) => name,
-            Self::File(ref name, _) => name,
-        }
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
     }
 
-    pub fn append_file(&mut self, file_name: &str, size: usize) -> Option<Rc<RefCell<Node>>> {
-        match self {
-            Self::Dir(_, childs) => {
-                let found;
-                {
-                    let borrow_child = childs.borrow();
-                    let res = borrow_child
-                        .iter()
-                        .find(|elem| elem.borrow().name() == file_name);
-                    match res {
-                        None => found = false,
-                        Some(_) => found = true,
-                    }
-                }
-
-                match found {
-                    false => {
-                        let new_nod =
-                            Rc::new(RefCell::new(Node::File(file_name.to_string(), size)));
-                        let mut childs = childs.borrow_mut();
-                        childs.push(new_nod.clone());
-                        Some(new_nod)
-                    }
-                    true => None,
-                }
-            }
-            Self::File(_, _) => None,
+    pub fn children(&self) -> &[NodeId] {
+        match &self.kind {
+            NodeKind::Dir(children) => children,
+            NodeKind::File => &[],
         }
     }
 
-    pub fn append_dir(&mut self, dir_name: &str) -> Option<Rc<RefCell<Node>>> {
-        match self {
-            Self::Dir(_, childs) => {
-                let found;
-                {
-                    let borrow_child = childs.borrow();
-                    let res = borrow_child
-                        .iter()
-                        .find(|elem| elem.borrow().name() == dir_name);
-                    match res {
-                        None => found = false,
-                        Some(_) => found = true,
-                    }
-                }
-                match found {
-                    false => {
-                        let new_nod = Rc::new(RefCell::new(Node::Dir(
-                            dir_name.to_string(),
-                            Rc::new(RefCell::new(Vec::<Rc<RefCell<Node>>>::new())),
-                        )));
-                        childs.borrow_mut().push(new_nod.clone());
-                        Some(new_nod)
-                    }
-                    true => None,
-                }
-            }
-            Self::File(_, _) => None,
-        }
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Dir(_))
     }
 
-    pub fn node_from_path(
-        root_node: Rc<RefCell<Node>>,
-        path: &VecDeque<String>,
-    ) -> Option<Rc<RefCell<Node>>> {
-        if path.is_empty() {
-            return Some(root_node);
-        }
-        let current_node = root_node;
-        let mut path = path.clone();
-        while let Some(name) = path.pop_front() {
-            match &*current_node.borrow() {
-                Node::Dir(_, childs) => {
-                    for child in childs.borrow().iter() {
-                        if child.borrow().name() == name && path.is_empty() {
-                            return Some(child.clone());
-                        }
-                        match &*child.borrow() {
-                            Node::Dir(subdir_name, _) => {
-                                if *subdir_name == name {
-                                    if let Some(node) = Self::node_from_path(child.clone(), &path) {
-                                        return Some(node);
-                                    }
-                                }
-                            }
-                            Node::File(_, _) => {}
-                        }
-                    }
-                }
-                Node::File(_, _) => {
-                    return None;
-                }
-            }
-        }
-        None
+    pub fn is_file(&self) -> bool {
+        matches!(self.kind, NodeKind::File)
     }
 }
-#[derive(PartialEq)]
-enum CurrentCommand {
-    Listing,
-    None,
-}
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FileSystem {
-    root_node: Rc<RefCell<Node>>,
+    nodes: Vec<Node>,
+}
+
+// Two file systems are equal when their trees match, whatever the order nodes were created in
+impl PartialEq for FileSystem {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_subtree(self.root(), other, other.root())
+    }
 }
 
 impl FileSystem {
     pub fn make_empty_fs() -> Self {
         Self {
-            root_node: Rc::new(RefCell::new(Node::Dir(
-                "/".to_string(),
-                Rc::new(RefCell::new(vec![])),
-            ))),
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                size: 0,
+                kind: NodeKind::Dir(vec![]),
+            }],
         }
     }
 
@@ -152,92 +98,142 @@ impl FileSystem {
         fs
     }
 
-    pub fn populate_from_listing(
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).children()
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent()
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.children(id)
+            .iter()
+            .find(|child| self.node(**child).name() == name)
+            .copied()
+    }
+
+    pub fn lookup<S: AsRef<str>>(&self, path: &[S]) -> Option<NodeId> {
+        path.iter()
+            .try_fold(self.root(), |id, name| self.child(id, name.as_ref()))
+    }
+
+    pub fn append_file(&mut self, dir: NodeId, file_name: &str, size: usize) -> Option<NodeId> {
+        self.append_node(dir, file_name, size, NodeKind::File)
+    }
+
+    pub fn append_dir(&mut self, dir: NodeId, dir_name: &str) -> Option<NodeId> {
+        self.append_node(dir, dir_name, 0, NodeKind::Dir(vec![]))
+    }
+
+    fn append_node(
         &mut self,
-        lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
-    ) {
-        let regex_cd = regex::Regex::new(r"^\$\s+cd\s+(?P<param>[a-zA-Z./]+)").unwrap();
-        let regex_ls = regex::Regex::new(r"^\$\s+ls").unwrap();
-        let regex_file = regex::Regex::new(r"(?P<size>\d+)\s+(?P<name>[a-zA-Z.]+)").unwrap();
-        let regex_dir = regex::Regex::new(r"dir\s+(?P<name>[a-zA-Z.]+)").unwrap();
-        let mut current_command = CurrentCommand::None;
-        let mut path = VecDeque::<String>::new();
-        for line in lines.flatten() {
-            if let Some(captures) = regex_cd.captures(&line) {
-                // Cd command
-                match captures.name("param").unwrap().as_str() {
-                    ".." => {
-                        if !path.is_empty() {
-                            path.pop_back();
-                        }
-                    }
-                    "/" => path.clear(),
-                    name => path.push_back(name.to_string()),
-                }
-            } else if regex_ls.captures(&line).is_some() {
-                current_command = CurrentCommand::Listing;
-            } else if current_command == CurrentCommand::Listing {
-                if let Some(captures) = regex_file.captures(&line) {
-                    let size = captures
-                        .name("size")
-                        .unwrap()
-                        .as_str()
-                        .parse::<usize>()
-                        .unwrap();
-                    let name = captures.name("name").unwrap().as_str();
-
-                    if let Some(node) = Node::node_from_path(self.root_node.clone(), &path) {
-                        node.borrow_mut().append_file(name, size);
-                    }
-                } else if let Some(captures) = regex_dir.captures(&line) {
-                    let name = captures.name("name").unwrap().as_str();
-                    if let Some(node) = Node::node_from_path(self.root_node.clone(), &path) {
-                        node.borrow_mut().append_dir(name);
-                    }
-                }
-            }
+        dir: NodeId,
+        name: &str,
+        size: usize,
+        kind: NodeKind,
+    ) -> Option<NodeId> {
+        if !self.node(dir).is_dir() || self.child(dir, name).is_some() {
+            return None;
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(dir),
+            size,
+            kind,
+        });
+        if let NodeKind::Dir(children) = &mut self.nodes[dir.0].kind {
+            children.push(id);
         }
+        let mut ancestor = Some(dir);
+        while let Some(current) = ancestor {
+            self.nodes[current.0].size += size;
+            ancestor = self.parent(current);
+        }
+        Some(id)
     }
 
-    pub fn size_of_directories(&self) -> Vec<(String, usize)> {
-        let mut result = vec![];
-        let mut vec_dirs = vec![];
-        let mut current_node = self.root_node.clone();
-
-        {
-            let current_node_borrow = current_node.borrow();
-            result.push((
-                current_node_borrow.name().to_string(),
-                current_node_borrow.size(),
-            ));
+    // Removed nodes stay in the arena as a detached subtree that no longer counts towards the root
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let Some(parent) = self.parent(id) else {
+            return false;
+        };
+        if let NodeKind::Dir(children) = &mut self.nodes[parent.0].kind {
+            let before = children.len();
+            children.retain(|child| *child != id);
+            if children.len() == before {
+                return false;
+            }
         }
+        self.nodes[id.0].parent = None;
+        let size = self.node(id).size();
+        let mut ancestor = Some(parent);
+        while let Some(current) = ancestor {
+            self.nodes[current.0].size -= size;
+            ancestor = self.parent(current);
+        }
+        true
+    }
 
-        loop {
-            match &*current_node.borrow() {
-                Node::Dir(_, childs) => {
-                    let childs_borrow = childs.borrow();
-                    for child in childs_borrow.iter() {
-                        match &*child.borrow() {
-                            Node::Dir(subdir_name, _) => {
-                                vec_dirs.push(child.clone());
-                                result.push((subdir_name.clone(), child.borrow().size()));
-                            }
-                            Node::File(_, _) => {}
-                        }
-                    }
-                }
-                Node::File(_, _) => {}
-            }
-            let next_node = vec_dirs.pop();
-            match next_node {
-                Some(node) => current_node = node.clone(),
-                None => break,
-            }
+    pub fn resize_file(&mut self, id: NodeId, size: usize) -> bool {
+        if !self.node(id).is_file() {
+            return false;
         }
+        let previous = self.node(id).size();
+        let mut ancestor = Some(id);
+        while let Some(current) = ancestor {
+            self.nodes[current.0].size = self.nodes[current.0].size - previous + size;
+            ancestor = self.parent(current);
+        }
+        true
+    }
 
+    // Depth first, parents before their content
+    pub fn directories(&self) -> Vec<NodeId> {
+        let mut result = vec![];
+        let mut pending = vec![self.root()];
+        while let Some(id) = pending.pop() {
+            result.push(id);
+            pending.extend(
+                self.children(id)
+                    .iter()
+                    .rev()
+                    .filter(|child| self.node(**child).is_dir()),
+            );
+        }
         result
     }
 
+    fn same_subtree(&self, id: NodeId, other: &FileSystem, other_id: NodeId) -> bool {
+        let node = self.node(id);
+        let other_node = other.node(other_id);
+        node.name() == other_node.name()
+            && node.is_dir() == other_node.is_dir()
+            && node.size() == other_node.size()
+            && node.children().len() == other_node.children().len()
+            && node.children().iter().all(|child| {
+                other
+                    .child(other_id, self.node(*child).name())
+                    .is_some_and(|other_child| self.same_subtree(*child, other, other_child))
+            })
+    }
+
+    pub fn size_of_directories(&self) -> Vec<(String, usize)> {
+        self.walk(WalkOrder::DepthFirst)
+            .filter(|(_, id)| self.node(*id).is_dir())
+            .map(|(path, id)| (path, self.node(id).size()))
+            .collect()
+    }
+
     pub fn total_size_directories(&self) -> usize {
         self.size_of_directories()
             .iter()
@@ -252,15 +248,9 @@ impl FileSystem {
     }
 
     pub fn directory_size_to_delete(&self) -> usize {
-        let total_size = self.max_directory_size();
-        let min_size_to_delete = 30000000 - (70000000 - total_size);
-        self.size_of_directories()
-            .iter()
-            .filter(|(_, size)| *size >= min_size_to_delete)
-            .fold(
-                total_size,
-                |accu, (_, size)| if *size < accu { *size } else { accu },
-            )
+        self.plan_cleanup(&CleanupPolicy::default(), CleanupStrategy::SingleDirectory)
+            .choice
+            .map_or(0, |choice| choice.size)
     }
 }
 
@@ -297,7 +287,65 @@ $ ls
             .to_string();
         let mut lines = read_from_string(&lines);
         let fs = FileSystem::make_from_listing(&mut lines);
-        println!("{:?}", fs);
         assert_eq!(fs.total_size_directories(), 95437);
+        assert_eq!(fs.directory_size_to_delete(), 24933642);
+    }
+
+    #[test]
+    fn test_structural_eq() {
+        let mut fs = FileSystem::make_empty_fs();
+        let a = fs.append_dir(fs.root(), "a").unwrap();
+        fs.append_file(a, "f", 10);
+        fs.append_file(fs.root(), "g", 20);
+
+        let mut other = FileSystem::make_empty_fs();
+        other.append_file(other.root(), "g", 20);
+        let a = other.append_dir(other.root(), "a").unwrap();
+        assert_ne!(fs, other);
+        let f = other.append_file(a, "f", 11).unwrap();
+        assert_ne!(fs, other);
+        other.resize_file(f, 10);
+        assert_eq!(fs, other);
+    }
+
+    #[test]
+    fn test_arena_navigation() {
+        let mut fs = FileSystem::make_empty_fs();
+        let a = fs.append_dir(fs.root(), "a").unwrap();
+        let e = fs.append_dir(a, "e").unwrap();
+        let i = fs.append_file(e, "i", 584).unwrap();
+        assert_eq!(fs.append_dir(a, "e"), None);
+        assert_eq!(fs.append_file(i, "x", 1), None);
+        fs.append_file(a, "f", 29116);
+
+        assert_eq!(fs.node(fs.root()).size(), 29700);
+        assert_eq!(fs.node(a).size(), 29700);
+        assert_eq!(fs.node(e).size(), 584);
+        assert_eq!(fs.lookup(&["a", "e", "i"]), Some(i));
+        assert_eq!(fs.lookup(&["a", "x"]), None);
+        assert_eq!(fs.lookup::<&str>(&[]), Some(fs.root()));
+        assert_eq!(fs.parent(i), Some(e));
+        assert_eq!(fs.parent(fs.root()), None);
+        assert_eq!(fs.children(a), &[e, fs.lookup(&["a", "f"]).unwrap()]);
+        assert!(fs.node(i).is_file());
+        assert_eq!(fs.directories(), vec![fs.root(), a, e]);
+
+        assert!(fs.resize_file(i, 600));
+        assert!(!fs.resize_file(e, 1));
+        assert_eq!(fs.node(a).size(), 29716);
+        assert!(fs.remove(e));
+        assert!(!fs.remove(e));
+        assert!(!fs.remove(fs.root()));
+        assert_eq!(fs.node(fs.root()).size(), 29116);
+        assert_eq!(fs.lookup(&["a", "e"]), None);
+
+        // Nodes inside a removed directory no longer affect the tree
+        assert!(fs.remove(i));
+        assert!(!fs.resize_file(e, 1));
+        let j = fs.append_file(e, "j", 10).unwrap();
+        assert!(fs.resize_file(j, 5));
+        assert_eq!(fs.node(e).size(), 5);
+        assert_eq!(fs.node(fs.root()).size(), 29116);
+        assert_eq!(fs.node(a).size(), 29116);
     }
 }
@@ -0,0 +1,232 @@
+use super::{FileSystem, NodeId};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkOrder {
+    DepthFirst,
+    BreadthFirst,
+}
+
+pub struct Walk<'a> {
+    fs: &'a FileSystem,
+    order: WalkOrder,
+    pending: VecDeque<(String, NodeId)>,
+}
+
+impl Iterator for Walk<'_> {
+    type Item = (String, NodeId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, id) = match self.order {
+            WalkOrder::DepthFirst => self.pending.pop_back()?,
+            WalkOrder::BreadthFirst => self.pending.pop_front()?,
+        };
+        let children = self
+            .fs
+            .children(id)
+            .iter()
+            .map(|child| (join_path(&path, self.fs.node(*child).name()), *child));
+        match self.order {
+            WalkOrder::DepthFirst => self.pending.extend(children.rev()),
+            WalkOrder::BreadthFirst => self.pending.extend(children),
+        }
+        Some((path, id))
+    }
+}
+
+pub(super) fn join_path(parent: &str, name: &str) -> String {
+    if parent == "/" {
+        format!("/{}", name)
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+// Wildcards inside a single name: '*' for any run of characters, '?' for one character
+pub(super) fn matches_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_name(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && matches_name(rest, &name[1..]),
+        Some((expected, rest)) => name.first() == Some(expected) && matches_name(rest, &name[1..]),
+    }
+}
+
+// '**' stands for any number of directories, including none
+fn matches_path(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((segment, rest)) if segment.iter().collect::<String>() == "**" => {
+            (0..=path.len()).any(|skip| matches_path(rest, &path[skip..]))
+        }
+        Some((segment, rest)) => {
+            !path.is_empty() && matches_name(segment, &path[0]) && matches_path(rest, &path[1..])
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn path_of(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            names.push(self.node(current).name());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // Absolute paths start from the root, others from the given directory
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let start = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+        split_path(path)
+            .iter()
+            .try_fold(start, |id, segment| match *segment {
+                "." => Some(id),
+                ".." => Some(self.parent(id).unwrap_or(id)),
+                name => self.child(id, name),
+            })
+    }
+
+    pub fn get(&self, path: &str) -> Option<NodeId> {
+        self.resolve(self.root(), path)
+    }
+
+    pub fn size_of(&self, path: &str) -> Option<usize> {
+        self.get(path).map(|id| self.node(id).size())
+    }
+
+    pub fn walk(&self, order: WalkOrder) -> Walk<'_> {
+        self.walk_from(self.root(), order)
+    }
+
+    pub fn walk_from(&self, id: NodeId, order: WalkOrder) -> Walk<'_> {
+        Walk {
+            fs: self,
+            order,
+            pending: VecDeque::from([(self.path_of(id), id)]),
+        }
+    }
+
+    pub fn find(&self, pattern: &str) -> Vec<(String, NodeId)> {
+        let pattern: Vec<Vec<char>> = split_path(pattern)
+            .iter()
+            .map(|segment| segment.chars().collect())
+            .collect();
+        self.walk(WalkOrder::DepthFirst)
+            .filter(|(path, _)| {
+                let path: Vec<Vec<char>> = split_path(path)
+                    .iter()
+                    .map(|segment| segment.chars().collect())
+                    .collect();
+                matches_path(&pattern, &path)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    fn sample() -> FileSystem {
+        let lines = r#"$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+dir d
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k"#
+            .to_string();
+        let mut lines = read_from_string(&lines);
+        FileSystem::make_from_listing(&mut lines)
+    }
+
+    fn paths(entries: Vec<(String, NodeId)>) -> Vec<String> {
+        entries.into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn test_paths() {
+        let fs = sample();
+        let e = fs.get("/a/e").unwrap();
+        assert_eq!(fs.path_of(e), "/a/e");
+        assert_eq!(fs.path_of(fs.root()), "/");
+        assert_eq!(fs.size_of("/a/e"), Some(584));
+        assert_eq!(fs.size_of("/"), Some(48381165));
+        assert_eq!(fs.size_of("/a/e/d"), Some(0));
+        assert_eq!(fs.size_of("/d"), Some(24933642));
+        assert_eq!(fs.get("/a/x"), None);
+        assert_eq!(fs.resolve(e, "../../d/j"), fs.get("/d/j"));
+        assert_eq!(fs.resolve(e, "./i"), fs.get("/a/e/i"));
+        assert_eq!(fs.resolve(e, "/"), Some(fs.root()));
+
+        let directories: Vec<String> = fs
+            .size_of_directories()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(directories, vec!["/", "/a", "/a/e", "/a/e/d", "/d"]);
+    }
+
+    #[test]
+    fn test_walk() {
+        let fs = sample();
+        let depth_first = paths(fs.walk(WalkOrder::DepthFirst).collect());
+        assert_eq!(
+            depth_first[..6],
+            ["/", "/a", "/a/e", "/a/e/i", "/a/e/d", "/a/f"]
+        );
+        let breadth_first = paths(fs.walk(WalkOrder::BreadthFirst).collect());
+        assert_eq!(breadth_first[..5], ["/", "/a", "/b.txt", "/c.dat", "/d"]);
+        assert_eq!(depth_first.len(), breadth_first.len());
+        assert_eq!(depth_first.len(), 15);
+        let d = fs.get("/d").unwrap();
+        let from_d = paths(fs.walk_from(d, WalkOrder::BreadthFirst).collect());
+        assert_eq!(from_d, ["/d", "/d/j", "/d/d.log", "/d/d.ext", "/d/k"]);
+    }
+
+    #[test]
+    fn test_find() {
+        let fs = sample();
+        assert_eq!(paths(fs.find("**/*.log")), ["/d/d.log"]);
+        assert_eq!(paths(fs.find("/*.*")), ["/b.txt", "/c.dat"]);
+        assert_eq!(paths(fs.find("**/d")), ["/a/e/d", "/d"]);
+        assert_eq!(
+            paths(fs.find("a/**/?")),
+            ["/a/e", "/a/e/i", "/a/e/d", "/a/f", "/a/g"]
+        );
+        assert_eq!(paths(fs.find("d/d.*")), ["/d/d.log", "/d/d.ext"]);
+        assert_eq!(fs.find("**").len(), 15);
+        assert!(fs.find("**/*.zip").is_empty());
+    }
+}
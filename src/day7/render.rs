@@ -0,0 +1,165 @@
+use super::{FileSystem, NodeId, WalkOrder};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub max_depth: Option<usize>,
+    pub human_readable: bool,
+}
+
+const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+
+pub fn human_size(size: usize) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+impl RenderOptions {
+    fn format_size(&self, size: usize) -> String {
+        if self.human_readable {
+            human_size(size)
+        } else {
+            size.to_string()
+        }
+    }
+
+    fn within_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max_depth| depth <= max_depth)
+    }
+}
+
+impl FileSystem {
+    fn depth(&self, id: NodeId) -> usize {
+        let mut depth = 0;
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            depth += 1;
+            current = parent;
+        }
+        depth
+    }
+
+    pub fn render_tree(&self, options: &RenderOptions) -> String {
+        self.render_tree_from(self.root(), options)
+    }
+
+    // Same layout as the puzzle statement, with sizes for directories too
+    pub fn render_tree_from(&self, id: NodeId, options: &RenderOptions) -> String {
+        let mut lines = vec![];
+        let mut pending = vec![(id, 0)];
+        while let Some((current, depth)) = pending.pop() {
+            let node = self.node(current);
+            lines.push(format!(
+                "{}- {} ({}, size={})",
+                "  ".repeat(depth),
+                node.name(),
+                if node.is_dir() { "dir" } else { "file" },
+                options.format_size(node.size())
+            ));
+            if options.within_depth(depth + 1) {
+                pending.extend(
+                    node.children()
+                        .iter()
+                        .rev()
+                        .map(|child| (*child, depth + 1)),
+                );
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn render_du(&self, options: &RenderOptions) -> String {
+        self.render_du_from(self.root(), options)
+    }
+
+    // Largest directories first, like `du | sort -rn`
+    pub fn render_du_from(&self, id: NodeId, options: &RenderOptions) -> String {
+        let start_depth = self.depth(id);
+        let mut directories: Vec<(String, usize)> = self
+            .walk_from(id, WalkOrder::DepthFirst)
+            .filter(|(_, id)| {
+                self.node(*id).is_dir() && options.within_depth(self.depth(*id) - start_depth)
+            })
+            .map(|(path, id)| (path, self.node(id).size()))
+            .collect();
+        directories.sort_by(|(path_a, size_a), (path_b, size_b)| {
+            size_b.cmp(size_a).then_with(|| path_a.cmp(path_b))
+        });
+        directories
+            .iter()
+            .map(|(path, size)| format!("{:>10}  {}", options.format_size(*size), path))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> FileSystem {
+        let mut fs = FileSystem::make_empty_fs();
+        let a = fs.append_dir(fs.root(), "a").unwrap();
+        let e = fs.append_dir(a, "e").unwrap();
+        fs.append_file(e, "i", 584);
+        fs.append_file(a, "f", 29116);
+        fs.append_file(fs.root(), "b.txt", 14848514);
+        let d = fs.append_dir(fs.root(), "d").unwrap();
+        fs.append_file(d, "j", 4060174);
+        fs
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let fs = sample();
+        assert_eq!(
+            fs.render_tree(&RenderOptions::default()),
+            r#"- / (dir, size=18938388)
+  - a (dir, size=29700)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+  - b.txt (file, size=14848514)
+  - d (dir, size=4060174)
+    - j (file, size=4060174)"#
+        );
+        let options = RenderOptions {
+            max_depth: Some(1),
+            human_readable: true,
+        };
+        assert_eq!(
+            fs.render_tree(&options),
+            r#"- / (dir, size=18.1M)
+  - a (dir, size=29.0K)
+  - b.txt (file, size=14.2M)
+  - d (dir, size=3.9M)"#
+        );
+    }
+
+    #[test]
+    fn test_render_du() {
+        let fs = sample();
+        assert_eq!(
+            fs.render_du(&RenderOptions::default()),
+            r#"  18938388  /
+   4060174  /d
+     29700  /a
+       584  /a/e"#
+        );
+        let options = RenderOptions {
+            max_depth: Some(0),
+            human_readable: true,
+        };
+        assert_eq!(fs.render_du(&options), "     18.1M  /");
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(3 * 1024 * 1024 * 1024), "3.0G");
+    }
+}
@@ -0,0 +1,314 @@
+use super::transcript::is_valid_name;
+use super::{FileSystem, NodeId};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Json(serde_json::Error),
+    InvalidNode(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Json(error) => write!(f, "invalid JSON: {}", error),
+            SnapshotError::InvalidNode(reason) => write!(f, "invalid node: {}", reason),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> Self {
+        SnapshotError::Json(error)
+    }
+}
+
+impl FileSystem {
+    // Empty directories are already created by the `dir` line of their parent.
+    // Names the transcript parser would refuse or alter cannot be written
+    pub fn to_transcript(&self) -> Result<String, SnapshotError> {
+        let mut lines = vec!["$ cd /".to_string()];
+        self.write_transcript(self.root(), &mut lines)?;
+        while lines.last().is_some_and(|line| line == "$ cd ..") {
+            lines.pop();
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn write_transcript(&self, dir: NodeId, lines: &mut Vec<String>) -> Result<(), SnapshotError> {
+        lines.push("$ ls".to_string());
+        for child in self.children(dir) {
+            let node = self.node(*child);
+            if !is_valid_name(node.name()) {
+                return Err(SnapshotError::InvalidNode(format!(
+                    "invalid name {:?} in {}",
+                    node.name(),
+                    self.path_of(dir)
+                )));
+            }
+            if node.is_dir() {
+                lines.push(format!("dir {}", node.name()));
+            } else {
+                lines.push(format!("{} {}", node.size(), node.name()));
+            }
+        }
+        for child in self.children(dir) {
+            let node = self.node(*child);
+            if node.is_dir() && !node.children().is_empty() {
+                lines.push(format!("$ cd {}", node.name()));
+                self.write_transcript(*child, lines)?;
+                lines.push("$ cd ..".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn node_to_json(&self, id: NodeId) -> Value {
+        let node = self.node(id);
+        if node.is_dir() {
+            let children: Vec<Value> = node
+                .children()
+                .iter()
+                .map(|child| self.node_to_json(*child))
+                .collect();
+            json!({
+                "name": node.name(),
+                "type": "dir",
+                "size": node.size(),
+                "children": children,
+            })
+        } else {
+            json!({
+                "name": node.name(),
+                "type": "file",
+                "size": node.size(),
+            })
+        }
+    }
+
+    pub fn to_json_value(&self) -> Value {
+        self.node_to_json(self.root())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json_value()).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        Self::from_json_value(&serde_json::from_str(json)?)
+    }
+
+    // Directory sizes are recomputed from the files, the stored ones are informative only
+    pub fn from_json_value(value: &Value) -> Result<Self, SnapshotError> {
+        let mut fs = Self::make_empty_fs();
+        let root = json_object(value)?;
+        if json_field(root, "type")?.as_str() != Some("dir") {
+            return Err(SnapshotError::InvalidNode(
+                "the root must be a directory".to_string(),
+            ));
+        }
+        let root_id = fs.root();
+        fs.load_children(root_id, root)?;
+        Ok(fs)
+    }
+
+    fn load_children(
+        &mut self,
+        dir: NodeId,
+        object: &Map<String, Value>,
+    ) -> Result<(), SnapshotError> {
+        let children = json_field(object, "children")?.as_array().ok_or_else(|| {
+            SnapshotError::InvalidNode("\"children\" must be an array".to_string())
+        })?;
+        for child in children {
+            let child = json_object(child)?;
+            let name = json_field(child, "name")?.as_str().ok_or_else(|| {
+                SnapshotError::InvalidNode("\"name\" must be a string".to_string())
+            })?;
+            if !is_valid_name(name) {
+                return Err(SnapshotError::InvalidNode(format!(
+                    "invalid name {:?} in {}",
+                    name,
+                    self.path_of(dir)
+                )));
+            }
+            let appended = match json_field(child, "type")?.as_str() {
+                Some("dir") => self.append_dir(dir, name),
+                Some("file") => {
+                    let size = json_field(child, "size")?.as_u64().ok_or_else(|| {
+                        SnapshotError::InvalidNode(format!("size of {} must be a number", name))
+                    })?;
+                    self.append_file(dir, name, usize::try_from(size).unwrap())
+                }
+                _ => {
+                    return Err(SnapshotError::InvalidNode(format!(
+                        "unknown type for {}",
+                        name
+                    )))
+                }
+            };
+            let id = appended.ok_or_else(|| {
+                SnapshotError::InvalidNode(format!(
+                    "duplicate entry {} in {}",
+                    name,
+                    self.path_of(dir)
+                ))
+            })?;
+            if self.node(id).is_dir() {
+                self.load_children(id, child)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn json_object(value: &Value) -> Result<&Map<String, Value>, SnapshotError> {
+    value
+        .as_object()
+        .ok_or_else(|| SnapshotError::InvalidNode("nodes must be objects".to_string()))
+}
+
+fn json_field<'a>(object: &'a Map<String, Value>, field: &str) -> Result<&'a Value, SnapshotError> {
+    object
+        .get(field)
+        .ok_or_else(|| SnapshotError::InvalidNode(format!("missing field \"{}\"", field)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    const LISTING: &str = r#"$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k"#;
+
+    fn parse(listing: &str) -> FileSystem {
+        let listing = listing.to_string();
+        let mut lines = read_from_string(&listing);
+        FileSystem::make_from_listing(&mut lines)
+    }
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let fs = parse(LISTING);
+        assert_eq!(fs.to_transcript().unwrap(), LISTING);
+        assert_eq!(parse(&fs.to_transcript().unwrap()), fs);
+
+        let mut fs = FileSystem::make_empty_fs();
+        let x = fs.append_dir(fs.root(), "x").unwrap();
+        fs.append_dir(x, "empty");
+        fs.append_file(fs.root(), "y", 10);
+        assert_eq!(
+            fs.to_transcript().unwrap(),
+            "$ cd /\n$ ls\ndir x\n10 y\n$ cd x\n$ ls\ndir empty"
+        );
+        assert_eq!(parse(&fs.to_transcript().unwrap()), fs);
+        assert_eq!(
+            FileSystem::make_empty_fs().to_transcript().unwrap(),
+            "$ cd /\n$ ls"
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let fs = parse(LISTING);
+        let json = fs.to_json();
+        assert_eq!(FileSystem::from_json(&json).unwrap(), fs);
+        let value = fs.to_json_value();
+        assert_eq!(value["size"], 48381165);
+        assert_eq!(value["children"][0]["children"][0]["name"], "e");
+        assert_eq!(value["children"][1]["type"], "file");
+
+        let parsed = FileSystem::from_json(
+            r#"{"name": "/", "type": "dir", "children": [
+                {"name": "a", "type": "dir", "size": 1, "children": [
+                    {"name": "f", "type": "file", "size": 12}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.size_of("/"), Some(12));
+    }
+
+    #[test]
+    fn test_json_errors() {
+        assert!(matches!(
+            FileSystem::from_json("{"),
+            Err(SnapshotError::Json(_))
+        ));
+        let error = FileSystem::from_json(
+            r#"{"type": "dir", "children": [
+                {"name": "f", "type": "file", "size": 1},
+                {"name": "f", "type": "file", "size": 2}
+            ]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "invalid node: duplicate entry f in /");
+        let error = FileSystem::from_json(r#"{"type": "file", "size": 3}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid node: the root must be a directory"
+        );
+    }
+
+    #[test]
+    fn test_json_transcript_round_trip() {
+        let fs = FileSystem::from_json(
+            r#"{"type": "dir", "children": [
+                {"name": "a b", "type": "dir", "children": [
+                    {"name": "f.txt", "type": "file", "size": 12}
+                ]},
+                {"name": "g", "type": "file", "size": 3}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(parse(&fs.to_transcript().unwrap()), fs);
+
+        let mut fs = FileSystem::make_empty_fs();
+        fs.append_file(fs.root(), "trail ", 3);
+        assert_eq!(
+            fs.to_transcript().unwrap_err().to_string(),
+            "invalid node: invalid name \"trail \" in /"
+        );
+    }
+
+    #[test]
+    fn test_json_invalid_names() {
+        for name in ["", ".", "..", "a/b", " lead", "trail ", "a\nb", "a\tb"] {
+            let json = format!(
+                r#"{{"type": "dir", "children": [{{"name": {:?}, "type": "file", "size": 3}}]}}"#,
+                name
+            );
+            let error = FileSystem::from_json(&json).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("invalid node: invalid name {:?} in /", name)
+            );
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use super::{FileSystem, NodeId, RenderOptions};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = r#"cd [path]                 change the current directory
+ls [path]                 list a directory
+pwd                       print the current directory
+du [-h] [-d depth] [path] directory sizes, largest first
+tree [-h] [-d depth] [path]
+find <pattern>            glob search, '**' matches any number of directories
+mkdir <path>              create a directory
+touch <size> <path>       create a file or change its size
+rm <path>                 remove a file or a directory with its content
+save <file>               write the file system as a transcript
+exit                      leave the shell"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellError {
+    UnknownCommand(String),
+    Usage(&'static str),
+    NoSuchPath(String),
+    NotADirectory(String),
+    AlreadyExists(String),
+    CannotRemoveRoot,
+    Io(String),
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShellError::UnknownCommand(command) => write!(f, "unknown command \"{}\"", command),
+            ShellError::Usage(usage) => write!(f, "usage: {}", usage),
+            ShellError::NoSuchPath(path) => write!(f, "{}: no such file or directory", path),
+            ShellError::NotADirectory(path) => write!(f, "{}: not a directory", path),
+            ShellError::AlreadyExists(path) => write!(f, "{}: already exists", path),
+            ShellError::CannotRemoveRoot => write!(f, "cannot remove /"),
+            ShellError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for ShellError {}
+
+pub struct Shell {
+    fs: FileSystem,
+    current: NodeId,
+    running: bool,
+}
+
+fn split_word(argument: &str) -> (&str, &str) {
+    argument
+        .split_once(char::is_whitespace)
+        .map_or((argument, ""), |(word, rest)| (word, rest.trim_start()))
+}
+
+// Leading -h and -d <depth> flags, the rest of the argument is a path
+fn parse_render_flags(argument: &str) -> Result<(RenderOptions, &str), ShellError> {
+    let mut options = RenderOptions::default();
+    let mut rest = argument;
+    loop {
+        let (flag, tail) = split_word(rest);
+        match flag {
+            "-h" => {
+                options.human_readable = true;
+                rest = tail;
+            }
+            "-d" => {
+                let (depth, tail) = split_word(tail);
+                options.max_depth =
+                    Some(depth.parse().map_err(|_| ShellError::Usage("-d <depth>"))?);
+                rest = tail;
+            }
+            _ => return Ok((options, rest)),
+        }
+    }
+}
+
+impl Shell {
+    pub fn new(fs: FileSystem) -> Self {
+        let current = fs.root();
+        Shell {
+            fs,
+            current,
+            running: true,
+        }
+    }
+
+    pub fn fs(&self) -> &FileSystem {
+        &self.fs
+    }
+
+    pub fn current_path(&self) -> String {
+        self.fs.path_of(self.current)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn resolve(&self, path: &str) -> Result<NodeId, ShellError> {
+        self.fs
+            .resolve(self.current, path)
+            .ok_or_else(|| ShellError::NoSuchPath(path.to_string()))
+    }
+
+    fn resolve_or_current(&self, path: &str) -> Result<NodeId, ShellError> {
+        if path.is_empty() {
+            Ok(self.current)
+        } else {
+            self.resolve(path)
+        }
+    }
+
+    // Splits "dir/name" into the existing parent directory and the new name
+    fn resolve_new(&self, path: &str, usage: &'static str) -> Result<(NodeId, String), ShellError> {
+        let path = path.trim_end_matches('/');
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(("", name)) => (self.fs.root(), name),
+            Some((parent, name)) => (self.resolve(parent)?, name),
+            None => (self.current, path),
+        };
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(ShellError::Usage(usage));
+        }
+        if !self.fs.node(parent).is_dir() {
+            return Err(ShellError::NotADirectory(self.fs.path_of(parent)));
+        }
+        Ok((parent, name.to_string()))
+    }
+
+    fn list(&self, id: NodeId) -> String {
+        let node = self.fs.node(id);
+        if node.is_file() {
+            return format!("{} {}", node.size(), node.name());
+        }
+        node.children()
+            .iter()
+            .map(|child| {
+                let child = self.fs.node(*child);
+                if child.is_dir() {
+                    format!("dir {}", child.name())
+                } else {
+                    format!("{} {}", child.size(), child.name())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<String, ShellError> {
+        let (command, argument) = split_word(line.trim());
+        match command {
+            "cd" => {
+                let id = if argument.is_empty() {
+                    self.fs.root()
+                } else {
+                    self.resolve(argument)?
+                };
+                if !self.fs.node(id).is_dir() {
+                    return Err(ShellError::NotADirectory(argument.to_string()));
+                }
+                self.current = id;
+                Ok(String::new())
+            }
+            "ls" => Ok(self.list(self.resolve_or_current(argument)?)),
+            "pwd" => Ok(self.current_path()),
+            "du" => {
+                let (options, path) = parse_render_flags(argument)?;
+                let id = self.resolve_or_current(path)?;
+                if !self.fs.node(id).is_dir() {
+                    return Err(ShellError::NotADirectory(path.to_string()));
+                }
+                Ok(self.fs.render_du_from(id, &options))
+            }
+            "tree" => {
+                let (options, path) = parse_render_flags(argument)?;
+                Ok(self
+                    .fs
+                    .render_tree_from(self.resolve_or_current(path)?, &options))
+            }
+            "find" => {
+                if argument.is_empty() {
+                    return Err(ShellError::Usage("find <pattern>"));
+                }
+                let pattern = if argument.starts_with('/') {
+                    argument.to_string()
+                } else {
+                    format!("{}/{}", self.current_path(), argument)
+                };
+                Ok(self
+                    .fs
+                    .find(&pattern)
+                    .into_iter()
+                    .map(|(path, _)| path)
+                    .collect::<Vec<String>>()
+                    .join("\n"))
+            }
+            "mkdir" => {
+                let (parent, name) = self.resolve_new(argument, "mkdir <path>")?;
+                self.fs
+                    .append_dir(parent, &name)
+                    .ok_or(ShellError::AlreadyExists(argument.to_string()))?;
+                Ok(String::new())
+            }
+            "touch" => {
+                const USAGE: &str = "touch <size> <path>";
+                let (size, path) = split_word(argument);
+                let size = size.parse().map_err(|_| ShellError::Usage(USAGE))?;
+                let (parent, name) = self.resolve_new(path, USAGE)?;
+                match self.fs.child(parent, &name) {
+                    Some(existing) if self.fs.resize_file(existing, size) => {}
+                    Some(_) => return Err(ShellError::AlreadyExists(path.to_string())),
+                    None => {
+                        self.fs.append_file(parent, &name, size);
+                    }
+                }
+                Ok(String::new())
+            }
+            "rm" => {
+                if argument.is_empty() {
+                    return Err(ShellError::Usage("rm <path>"));
+                }
+                let id = self.resolve(argument)?;
+                let parent = self.fs.parent(id).ok_or(ShellError::CannotRemoveRoot)?;
+                // Leave a removed directory before it disappears
+                let mut current = Some(self.current);
+                while let Some(ancestor) = current {
+                    if ancestor == id {
+                        self.current = parent;
+                        break;
+                    }
+                    current = self.fs.parent(ancestor);
+                }
+                self.fs.remove(id);
+                Ok(String::new())
+            }
+            "save" => {
+                if argument.is_empty() {
+                    return Err(ShellError::Usage("save <file>"));
+                }
+                let transcript = self
+                    .fs
+                    .to_transcript()
+                    .map_err(|error| ShellError::Io(error.to_string()))?;
+                fs::write(argument, format!("{}\n", transcript))
+                    .map_err(|error| ShellError::Io(format!("{}: {}", argument, error)))?;
+                Ok(format!(
+                    "saved {} lines to {}",
+                    transcript.lines().count(),
+                    argument
+                ))
+            }
+            "help" => Ok(HELP.to_string()),
+            "exit" | "quit" => {
+                self.running = false;
+                Ok(String::new())
+            }
+            _ => Err(ShellError::UnknownCommand(command.to_string())),
+        }
+    }
+
+    // Scripts echo each command like a terminal transcript, interactive sessions show a prompt
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        output: &mut W,
+        interactive: bool,
+    ) -> io::Result<usize> {
+        let mut errors = 0;
+        let mut lines = input.lines();
+        while self.running {
+            if interactive {
+                write!(output, "{} $ ", self.current_path())?;
+                output.flush()?;
+            }
+            let Some(line) = lines.next() else {
+                break;
+            };
+            let line = line?;
+            let command = line.trim();
+            if command.is_empty() || command.starts_with('#') {
+                continue;
+            }
+            if !interactive {
+                writeln!(output, "$ {}", command)?;
+            }
+            match self.execute(command) {
+                Ok(result) if result.is_empty() => {}
+                Ok(result) => writeln!(output, "{}", result)?,
+                Err(error) => {
+                    errors += 1;
+                    writeln!(output, "error: {}", error)?;
+                }
+            }
+        }
+        Ok(errors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    fn run_script(shell: &mut Shell, script: &str) -> (String, usize) {
+        let mut output = vec![];
+        let errors = shell.run(script.as_bytes(), &mut output, false).unwrap();
+        (String::from_utf8(output).unwrap(), errors)
+    }
+
+    #[test]
+    fn test_shell_script() {
+        let mut shell = Shell::new(FileSystem::make_empty_fs());
+        let (output, errors) = run_script(
+            &mut shell,
+            r#"# build a small tree
+mkdir a
+mkdir a/my docs
+touch 100 a/my docs/report.txt
+cd a
+touch 20 f
+pwd
+ls
+du
+find **/*.txt
+cd /
+touch 5 a/f
+tree -d 1
+rm a/my docs
+du -h
+frobnicate
+cd a/f
+exit
+pwd"#,
+        );
+        assert_eq!(
+            output,
+            r#"$ mkdir a
+$ mkdir a/my docs
+$ touch 100 a/my docs/report.txt
+$ cd a
+$ touch 20 f
+$ pwd
+/a
+$ ls
+dir my docs
+20 f
+$ du
+       120  /a
+       100  /a/my docs
+$ find **/*.txt
+/a/my docs/report.txt
+$ cd /
+$ touch 5 a/f
+$ tree -d 1
+- / (dir, size=105)
+  - a (dir, size=105)
+$ rm a/my docs
+$ du -h
+         5  /
+         5  /a
+$ frobnicate
+error: unknown command "frobnicate"
+$ cd a/f
+error: a/f: not a directory
+$ exit
+"#
+        );
+        assert_eq!(errors, 2);
+        assert!(!shell.is_running());
+        assert_eq!(shell.fs().size_of("/"), Some(5));
+    }
+
+    #[test]
+    fn test_shell_errors() {
+        let mut shell = Shell::new(FileSystem::make_empty_fs());
+        shell.execute("mkdir a").unwrap();
+        shell.execute("cd a").unwrap();
+        assert_eq!(
+            shell.execute("mkdir /a"),
+            Err(ShellError::AlreadyExists("/a".to_string()))
+        );
+        assert_eq!(
+            shell.execute("touch big x"),
+            Err(ShellError::Usage("touch <size> <path>"))
+        );
+        assert_eq!(
+            shell.execute("cd missing"),
+            Err(ShellError::NoSuchPath("missing".to_string()))
+        );
+        assert_eq!(shell.execute("rm /"), Err(ShellError::CannotRemoveRoot));
+        assert_eq!(shell.execute("rm ."), Ok(String::new()));
+        assert_eq!(shell.current_path(), "/");
+        assert_eq!(shell.execute("ls"), Ok(String::new()));
+    }
+
+    #[test]
+    fn test_shell_save() {
+        let path = std::env::temp_dir().join(format!("day7-shell-{}.txt", std::process::id()));
+        let mut shell = Shell::new(FileSystem::make_empty_fs());
+        let script = format!(
+            "mkdir logs\ntouch 42 logs/app.log\ntouch 7 readme\nsave {}",
+            path.display()
+        );
+        let (output, errors) = run_script(&mut shell, &script);
+        assert_eq!(errors, 0);
+        assert!(output.ends_with(&format!("saved 7 lines to {}\n", path.display())));
+
+        let saved = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let mut lines = read_from_string(&saved);
+        assert_eq!(FileSystem::make_from_listing(&mut lines), *shell.fs());
+    }
+}
@@ -0,0 +1,237 @@
+use super::{FileSystem, NodeId, TranscriptIssue, TranscriptIssueKind};
+use std::io;
+
+enum TranscriptLine<'a> {
+    ChangeDirectory(&'a str),
+    List,
+    Command(&'a str),
+    Dir(&'a str),
+    File(usize, &'a str),
+    Output,
+}
+
+// Lines are trimmed and split on newlines, so names keep no outer whitespace or control character
+pub(super) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name.trim() == name
+        && !name.contains('/')
+        && !name.contains(char::is_control)
+}
+
+// Names run until the end of the line
+fn parse_line(line: &str) -> TranscriptLine<'_> {
+    if let Some(command) = line.strip_prefix('$') {
+        let command = command.trim();
+        return match command.split_once(char::is_whitespace) {
+            Some(("cd", path)) => TranscriptLine::ChangeDirectory(path.trim_start()),
+            None if command == "ls" => TranscriptLine::List,
+            _ => TranscriptLine::Command(command),
+        };
+    }
+    match line.split_once(char::is_whitespace) {
+        Some(("dir", name)) => TranscriptLine::Dir(name.trim_start()),
+        Some((size, name)) => match size.parse::<usize>() {
+            Ok(size) => TranscriptLine::File(size, name.trim_start()),
+            Err(_) => TranscriptLine::Output,
+        },
+        None => TranscriptLine::Output,
+    }
+}
+
+impl FileSystem {
+    pub fn populate_from_listing(
+        &mut self,
+        lines: &mut dyn Iterator<Item = Result<String, io::Error>>,
+    ) -> Vec<TranscriptIssue> {
+        let mut issues = vec![];
+        let mut current = self.root();
+        let mut listing = false;
+        for (index, line) in lines.enumerate() {
+            let Ok(line) = line else {
+                continue;
+            };
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let issue = match parse_line(line) {
+                TranscriptLine::ChangeDirectory(path) => {
+                    listing = false;
+                    let (directory, issue) = self.change_directory(current, path);
+                    current = directory;
+                    issue
+                }
+                TranscriptLine::List => {
+                    listing = true;
+                    None
+                }
+                TranscriptLine::Command(command) => {
+                    listing = false;
+                    Some(TranscriptIssueKind::UnknownCommand(command.to_string()))
+                }
+                TranscriptLine::Dir(name) if listing => self.list_entry(current, name, None),
+                TranscriptLine::File(size, name) if listing => {
+                    self.list_entry(current, name, Some(size))
+                }
+                _ => Some(TranscriptIssueKind::UnexpectedOutput(line.to_string())),
+            };
+            if let Some(kind) = issue {
+                issues.push(TranscriptIssue::new(index + 1, kind));
+            }
+        }
+        issues
+    }
+
+    // Directories that were never listed are created on the way
+    fn change_directory(
+        &mut self,
+        current: NodeId,
+        path: &str,
+    ) -> (NodeId, Option<TranscriptIssueKind>) {
+        let mut directory = if path.starts_with('/') {
+            self.root()
+        } else {
+            current
+        };
+        let mut issue = None;
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            match segment {
+                "." => {}
+                ".." => directory = self.parent(directory).unwrap_or(directory),
+                name => match self.child(directory, name) {
+                    Some(child) if self.node(child).is_dir() => directory = child,
+                    Some(child) => {
+                        let path = self.path_of(child);
+                        return (current, Some(TranscriptIssueKind::NotADirectory(path)));
+                    }
+                    None => {
+                        directory = self.append_dir(directory, name).unwrap();
+                        if issue.is_none() {
+                            let path = self.path_of(directory);
+                            issue = Some(TranscriptIssueKind::UnknownDirectory(path));
+                        }
+                    }
+                },
+            }
+        }
+        (directory, issue)
+    }
+
+    fn list_entry(
+        &mut self,
+        directory: NodeId,
+        name: &str,
+        size: Option<usize>,
+    ) -> Option<TranscriptIssueKind> {
+        if !is_valid_name(name) {
+            return Some(TranscriptIssueKind::InvalidName(name.to_string()));
+        }
+        let Some(existing) = self.child(directory, name) else {
+            match size {
+                Some(size) => self.append_file(directory, name, size),
+                None => self.append_dir(directory, name),
+            };
+            return None;
+        };
+        let node = self.node(existing);
+        match size {
+            None if node.is_dir() => None,
+            Some(size) if node.is_file() && node.size() == size => None,
+            Some(size) if node.is_file() => Some(TranscriptIssueKind::ConflictingSize {
+                path: self.path_of(existing),
+                previous: node.size(),
+                listed: size,
+            }),
+            _ => Some(TranscriptIssueKind::ConflictingType(self.path_of(existing))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testtools::*;
+
+    #[test]
+    fn test_any_file_name() {
+        let lines = r#"$ cd /
+$ ls
+dir my-dir_2
+100 report 2024.txt
+dir bin
+$ cd my-dir_2
+$ ls
+dir nested dir
+7 .hidden
+$ cd /my-dir_2/nested dir
+$ ls
+42 a-b_c
+$ cd /bin
+$ ls
+5 x
+$ ls
+5 x
+dir ..lib"#
+            .to_string();
+        let mut lines = read_from_string(&lines);
+        let mut fs = FileSystem::make_empty_fs();
+        let issues = fs.populate_from_listing(&mut lines);
+        assert_eq!(issues, vec![]);
+        assert_eq!(fs.size_of("/report 2024.txt"), Some(100));
+        assert_eq!(fs.size_of("/my-dir_2/nested dir/a-b_c"), Some(42));
+        assert_eq!(fs.size_of("/my-dir_2"), Some(49));
+        assert_eq!(fs.size_of("/bin"), Some(5));
+        assert!(fs.get("/bin/..lib").is_some());
+        assert_eq!(fs.size_of("/"), Some(154));
+    }
+
+    #[test]
+    fn test_transcript_issues() {
+        let lines = r#"$ cd /
+12 early
+$ ls
+dir a
+10 f
+$ pwd
+$ cd a/b
+$ ls
+1 g
+$ cd /f
+$ ls
+20 h
+$ cd /
+$ ls
+11 f
+f
+dir f
+7 a
+$ ls -la"#
+            .to_string();
+        let mut lines = read_from_string(&lines);
+        let mut fs = FileSystem::make_empty_fs();
+        let issues: Vec<String> = fs
+            .populate_from_listing(&mut lines)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "line 2: warning: unexpected output \"12 early\"",
+                "line 6: error: unknown command \"pwd\"",
+                "line 7: warning: directory /a/b was never listed, created it",
+                "line 10: error: /f is not a directory",
+                "line 15: error: /f listed with size 11 but was 10 before",
+                "line 16: warning: unexpected output \"f\"",
+                "line 17: error: /f listed both as a file and a directory",
+                "line 18: error: /a listed both as a file and a directory",
+                "line 19: error: unknown command \"ls -la\"",
+            ]
+        );
+        assert_eq!(fs.size_of("/a/b/g"), Some(1));
+        assert_eq!(fs.size_of("/a/b/h"), Some(20));
+        assert_eq!(fs.size_of("/"), Some(31));
+    }
+}
@@ -0,0 +1,46 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::process;
+
+const USAGE: &str = "Usage: day7diff [--json] BEFORE AFTER";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn load(path: &str) -> adventofcode::day7::FileSystem {
+    use adventofcode::day7::*;
+    let file = File::open(path).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path, error);
+        process::exit(1);
+    });
+    let mut fs = FileSystem::make_empty_fs();
+    for issue in fs.populate_from_listing(&mut BufReader::new(file).lines()) {
+        eprintln!("{}: {}", path, issue);
+    }
+    fs
+}
+
+fn main() {
+    let mut json = false;
+    let mut paths = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ if arg.starts_with('-') => usage(),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() != 2 {
+        usage();
+    }
+
+    let diff = load(&paths[0]).diff(&load(&paths[1]));
+    if json {
+        println!("{}", diff.to_json());
+    } else if !diff.is_empty() {
+        println!("{}", diff);
+    }
+}
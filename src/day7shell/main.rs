@@ -0,0 +1,59 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal};
+use std::process;
+
+const USAGE: &str = "Usage: day7shell [TRANSCRIPT] [--script FILE]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn open(path: &str) -> BufReader<File> {
+    match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    use adventofcode::day7::*;
+    let mut transcript = None;
+    let mut script = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--script" => script = Some(args.next().unwrap_or_else(|| usage())),
+            _ if arg.starts_with('-') || transcript.is_some() => usage(),
+            _ => transcript = Some(arg),
+        }
+    }
+
+    let mut fs = FileSystem::make_empty_fs();
+    if let Some(transcript) = transcript {
+        for issue in fs.populate_from_listing(&mut open(&transcript).lines()) {
+            eprintln!("{}", issue);
+        }
+    }
+    let mut shell = Shell::new(fs);
+    let mut stdout = io::stdout();
+    let result = match script {
+        Some(script) => shell.run(open(&script), &mut stdout, false),
+        None => {
+            let interactive = io::stdin().is_terminal();
+            shell.run(io::stdin().lock(), &mut stdout, interactive)
+        }
+    };
+    match result {
+        Ok(0) => {}
+        Ok(_) => process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
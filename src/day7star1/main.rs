@@ -1,8 +1,44 @@
+use std::env;
 use std::io;
+use std::process;
+
+const USAGE: &str = "Usage: day7star1 [--tree] [--du] [--max-depth N] [--human-readable]";
 
 fn main() {
     use adventofcode::day7::*;
+    let mut show_tree = false;
+    let mut show_du = false;
+    let mut options = RenderOptions::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tree" => show_tree = true,
+            "--du" => show_du = true,
+            "--human-readable" | "-h" => options.human_readable = true,
+            "--max-depth" => match args.next().and_then(|depth| depth.parse().ok()) {
+                Some(depth) => options.max_depth = Some(depth),
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(1);
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(1);
+            }
+        }
+    }
+
     let mut lines = io::stdin().lines();
-    let fs = FileSystem::make_from_listing(&mut lines);
+    let mut fs = FileSystem::make_empty_fs();
+    for issue in fs.populate_from_listing(&mut lines) {
+        eprintln!("{}", issue);
+    }
+    if show_tree {
+        println!("{}", fs.render_tree(&options));
+    }
+    if show_du {
+        println!("{}", fs.render_du(&options));
+    }
     println!("Total size of directorie = {}", fs.total_size_directories());
 }
@@ -1,8 +1,64 @@
+use std::env;
 use std::io;
+use std::process;
+
+const USAGE: &str = "Usage: day7star2 [--tree] [--du] [--max-depth N] [--human-readable] \
+[--capacity N] [--required N] [--set] [--explain]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn parse_number(value: Option<String>) -> usize {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| usage())
+}
 
 fn main() {
     use adventofcode::day7::*;
+    let mut show_tree = false;
+    let mut show_du = false;
+    let mut explain = false;
+    let mut options = RenderOptions::default();
+    let mut policy = CleanupPolicy::default();
+    let mut strategy = CleanupStrategy::SingleDirectory;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tree" => show_tree = true,
+            "--du" => show_du = true,
+            "--human-readable" | "-h" => options.human_readable = true,
+            "--max-depth" => options.max_depth = Some(parse_number(args.next())),
+            "--capacity" => policy.capacity = parse_number(args.next()),
+            "--required" => policy.required_free = parse_number(args.next()),
+            "--set" => strategy = CleanupStrategy::DirectorySet,
+            "--explain" => explain = true,
+            _ => usage(),
+        }
+    }
+
     let mut lines = io::stdin().lines();
-    let fs = FileSystem::make_from_listing(&mut lines);
-    println!("Size to delete = {}", fs.directory_size_to_delete());
+    let mut fs = FileSystem::make_empty_fs();
+    for issue in fs.populate_from_listing(&mut lines) {
+        eprintln!("{}", issue);
+    }
+    if show_tree {
+        println!("{}", fs.render_tree(&options));
+    }
+    if show_du {
+        println!("{}", fs.render_du(&options));
+    }
+    let plan = fs.plan_cleanup(&policy, strategy);
+    if explain {
+        println!("{}", plan);
+    }
+    match plan.choice {
+        Some(choice) => println!("Size to delete = {}", choice.size),
+        None => {
+            eprintln!("No deletion frees enough space");
+            process::exit(1);
+        }
+    }
 }